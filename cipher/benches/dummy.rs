@@ -0,0 +1,209 @@
+//! Benchmarks of dummy ciphers which check that the `criterion`-based benchmark
+//! macros compile for every supported cipher initialization form.
+// `criterion_group!` generates undocumented public functions
+#![allow(missing_docs)]
+
+use cipher::{KeyInit, KeyIvInit};
+use criterion::{criterion_group, criterion_main};
+use dummy::{DummyCbc, DummyCipher, DummyStream};
+
+mod dummy {
+    use cipher::{
+        Block, BlockCipherDecBackend, BlockCipherDecClosure, BlockCipherDecrypt,
+        BlockCipherEncBackend, BlockCipherEncClosure, BlockCipherEncrypt, BlockModeDecBackend,
+        BlockModeDecClosure, BlockModeDecrypt, BlockModeEncBackend, BlockModeEncClosure,
+        BlockModeEncrypt, BlockSizeUser, InOut, InOutBuf, IvSizeUser, KeyInit, KeyIvInit,
+        KeySizeUser, ParBlocksSizeUser, StreamCipher, StreamCipherError,
+        consts::{U1, U16},
+    };
+
+    /// Dummy insecure block cipher which adds key to the block.
+    #[derive(Clone, Copy, Debug)]
+    pub struct DummyCipher(u128);
+
+    impl KeySizeUser for DummyCipher {
+        type KeySize = U16;
+    }
+
+    impl KeyInit for DummyCipher {
+        fn new(key: &cipher::Key<Self>) -> Self {
+            Self(u128::from_le_bytes(key.0))
+        }
+    }
+
+    impl BlockSizeUser for DummyCipher {
+        type BlockSize = U16;
+    }
+
+    impl ParBlocksSizeUser for DummyCipher {
+        type ParBlocksSize = U1;
+    }
+
+    impl BlockCipherEncrypt for DummyCipher {
+        fn encrypt_with_backend(&self, f: impl BlockCipherEncClosure<BlockSize = U16>) {
+            f.call(self);
+        }
+    }
+
+    impl BlockCipherDecrypt for DummyCipher {
+        fn decrypt_with_backend(&self, f: impl BlockCipherDecClosure<BlockSize = U16>) {
+            f.call(self);
+        }
+    }
+
+    impl BlockCipherEncBackend for DummyCipher {
+        fn encrypt_block(&self, mut block: InOut<'_, '_, Block<Self>>) {
+            let x = u128::from_le_bytes(block.get_in().0).wrapping_add(self.0);
+            *block.get_out() = x.to_le_bytes().into();
+        }
+    }
+
+    impl BlockCipherDecBackend for DummyCipher {
+        fn decrypt_block(&self, mut block: InOut<'_, '_, Block<Self>>) {
+            let x = u128::from_le_bytes(block.get_in().0).wrapping_sub(self.0);
+            *block.get_out() = x.to_le_bytes().into();
+        }
+    }
+
+    /// Dummy CBC mode over [`DummyCipher`].
+    #[derive(Clone, Copy, Debug)]
+    pub struct DummyCbc {
+        cipher: DummyCipher,
+        iv: Block<DummyCipher>,
+    }
+
+    impl KeySizeUser for DummyCbc {
+        type KeySize = U16;
+    }
+
+    impl IvSizeUser for DummyCbc {
+        type IvSize = U16;
+    }
+
+    impl KeyIvInit for DummyCbc {
+        fn new(key: &cipher::Key<Self>, iv: &cipher::Iv<Self>) -> Self {
+            Self {
+                cipher: DummyCipher::new(key),
+                iv: *iv,
+            }
+        }
+    }
+
+    impl BlockSizeUser for DummyCbc {
+        type BlockSize = U16;
+    }
+
+    impl ParBlocksSizeUser for DummyCbc {
+        type ParBlocksSize = U1;
+    }
+
+    impl BlockModeEncrypt for DummyCbc {
+        fn encrypt_with_backend(&mut self, f: impl BlockModeEncClosure<BlockSize = U16>) {
+            f.call(self);
+        }
+    }
+
+    impl BlockModeDecrypt for DummyCbc {
+        fn decrypt_with_backend(&mut self, f: impl BlockModeDecClosure<BlockSize = U16>) {
+            f.call(self);
+        }
+    }
+
+    impl BlockModeEncBackend for DummyCbc {
+        fn encrypt_block(&mut self, mut block: InOut<'_, '_, Block<Self>>) {
+            let mut t = block.clone_in();
+            t.iter_mut().zip(&self.iv).for_each(|(a, b)| *a ^= b);
+            BlockCipherEncrypt::encrypt_block(&self.cipher, &mut t);
+            self.iv = t;
+            *block.get_out() = t;
+        }
+    }
+
+    impl BlockModeDecBackend for DummyCbc {
+        fn decrypt_block(&mut self, mut block: InOut<'_, '_, Block<Self>>) {
+            let ct = block.clone_in();
+            let mut t = ct;
+            BlockCipherDecrypt::decrypt_block(&self.cipher, &mut t);
+            t.iter_mut().zip(&self.iv).for_each(|(a, b)| *a ^= b);
+            self.iv = ct;
+            *block.get_out() = t;
+        }
+    }
+
+    /// Dummy insecure stream cipher which can be initialized with or without IV.
+    #[derive(Clone, Copy, Debug)]
+    pub struct DummyStream(pub u8);
+
+    impl KeySizeUser for DummyStream {
+        type KeySize = U16;
+    }
+
+    impl IvSizeUser for DummyStream {
+        type IvSize = U16;
+    }
+
+    impl KeyInit for DummyStream {
+        fn new(key: &cipher::Key<Self>) -> Self {
+            Self(key[0])
+        }
+    }
+
+    impl KeyIvInit for DummyStream {
+        fn new(key: &cipher::Key<Self>, iv: &cipher::Iv<Self>) -> Self {
+            Self(key[0] ^ iv[0])
+        }
+    }
+
+    impl StreamCipher for DummyStream {
+        fn check_remaining(&self, _data_len: usize) -> Result<(), StreamCipherError> {
+            Ok(())
+        }
+
+        fn unchecked_apply_keystream_inout(&mut self, mut buf: InOutBuf<'_, '_, u8>) {
+            for i in 0..buf.len() {
+                let mut b = buf.get(i);
+                *b.get_out() = *b.get_in() ^ self.0;
+                self.0 = self.0.wrapping_add(1);
+            }
+        }
+
+        fn unchecked_write_keystream(&mut self, buf: &mut [u8]) {
+            for b in buf {
+                *b = self.0;
+                self.0 = self.0.wrapping_add(1);
+            }
+        }
+    }
+}
+
+cipher::bench_block_encryptor!(Key: DummyCipher, cipher_encrypt);
+cipher::bench_block_decryptor!(Key: DummyCipher, cipher_decrypt);
+cipher::bench_block_encryptor!(KeyIv: DummyCbc, cbc_encrypt);
+cipher::bench_block_decryptor!(KeyIv: DummyCbc, cbc_decrypt);
+cipher::bench_block_encryptor!(
+    { DummyCipher::new(&[1; 16].into()) },
+    DummyCipher,
+    custom_encrypt
+);
+cipher::bench_block_decryptor!(
+    { DummyCbc::new(&[1; 16].into(), &[2; 16].into()) },
+    DummyCbc,
+    custom_decrypt
+);
+cipher::bench_stream_cipher!(Key: DummyStream, stream_key);
+cipher::bench_stream_cipher!(KeyIv: DummyStream, stream_key_iv);
+cipher::bench_stream_cipher!({ DummyStream(42) }, DummyStream, stream_custom);
+
+criterion_group!(
+    benches,
+    cipher_encrypt,
+    cipher_decrypt,
+    cbc_encrypt,
+    cbc_decrypt,
+    custom_encrypt,
+    custom_decrypt,
+    stream_key,
+    stream_key_iv,
+    stream_custom,
+);
+criterion_main!(benches);
@@ -13,6 +13,7 @@
 #[cfg(all(feature = "block-padding", feature = "alloc"))]
 use alloc::{vec, vec::Vec};
 use common::{Block, BlockSizeUser};
+use core::marker::PhantomData;
 use inout::{InOut, InOutBuf, NotEqualError};
 #[cfg(feature = "block-padding")]
 use inout::{
@@ -21,13 +22,30 @@ use inout::{
 };
 
 mod backends;
+#[cfg(feature = "ct-padding")]
+mod ct_padding;
 mod ctx;
+#[cfg(feature = "block-mode-buffer")]
+mod mode_buffer;
+mod multi_key;
 
+#[cfg(feature = "ct-padding")]
+use ctutils::CtOption;
+pub(crate) use ctx::BackendInfoCtx;
 use ctx::{BlockCtx, BlocksCtx};
 
 pub use backends::{
-    BlockCipherDecBackend, BlockCipherDecClosure, BlockCipherEncBackend, BlockCipherEncClosure,
-    BlockModeDecBackend, BlockModeDecClosure, BlockModeEncBackend, BlockModeEncClosure,
+    BackendInfo, BlockCipherDecBackend, BlockCipherDecClosure, BlockCipherEncBackend,
+    BlockCipherEncClosure, BlockModeDecBackend, BlockModeDecClosure, BlockModeEncBackend,
+    BlockModeEncClosure,
+};
+#[cfg(feature = "ct-padding")]
+pub use ct_padding::CtPadding;
+#[cfg(feature = "block-mode-buffer")]
+pub use mode_buffer::BlockModeBuffer;
+pub use multi_key::{
+    KeyCountUser, MultiKeyBlockCipherEncBackend, MultiKeyBlockCipherEncClosure,
+    MultiKeyBlockCipherEncrypt, MultiKeyBlocks, MultiKeyFallback,
 };
 
 /// Encrypt-only functionality for block ciphers.
@@ -82,6 +100,20 @@ pub trait BlockCipherEncrypt: BlockSizeUser + Sized {
         InOutBuf::new(in_blocks, out_blocks)
             .map(|blocks| self.encrypt_with_backend(BlocksCtx { blocks }))
     }
+
+    /// Get information about the backend used for encryption.
+    ///
+    /// # Panics
+    /// If implementation of `encrypt_with_backend` does not call the provided closure.
+    #[inline]
+    fn encrypt_backend_info(&self) -> BackendInfo {
+        let mut info = None;
+        self.encrypt_with_backend(BackendInfoCtx {
+            info: &mut info,
+            _pd: PhantomData,
+        });
+        info.expect("backend closure was not called")
+    }
 }
 
 /// Decrypt-only functionality for block ciphers.
@@ -136,6 +168,20 @@ pub trait BlockCipherDecrypt: BlockSizeUser {
         InOutBuf::new(in_blocks, out_blocks)
             .map(|blocks| self.decrypt_with_backend(BlocksCtx { blocks }))
     }
+
+    /// Get information about the backend used for decryption.
+    ///
+    /// # Panics
+    /// If implementation of `decrypt_with_backend` does not call the provided closure.
+    #[inline]
+    fn decrypt_backend_info(&self) -> BackendInfo {
+        let mut info = None;
+        self.decrypt_with_backend(BackendInfoCtx {
+            info: &mut info,
+            _pd: PhantomData,
+        });
+        info.expect("backend closure was not called")
+    }
 }
 
 impl<Alg: BlockCipherEncrypt> BlockCipherEncrypt for &Alg {
@@ -288,6 +334,20 @@ pub trait BlockModeEncrypt: BlockSizeUser + Sized {
         buf.truncate(res_len);
         buf
     }
+
+    /// Get information about the backend used for encryption.
+    ///
+    /// # Panics
+    /// If implementation of `encrypt_with_backend` does not call the provided closure.
+    #[inline]
+    fn encrypt_backend_info(&mut self) -> BackendInfo {
+        let mut info = None;
+        self.encrypt_with_backend(BackendInfoCtx {
+            info: &mut info,
+            _pd: PhantomData,
+        });
+        info.expect("backend closure was not called")
+    }
 }
 
 /// Decrypt-only functionality for block ciphers and modes with mutable access to `self`.
@@ -413,4 +473,88 @@ pub trait BlockModeDecrypt: BlockSizeUser + Sized {
         out.truncate(len);
         Ok(out)
     }
+
+    /// Decrypt input and unpad it in constant time. Returns resulting plaintext slice.
+    ///
+    /// Unlike [`decrypt_padded_inout`][Self::decrypt_padded_inout], execution time
+    /// of this method does not depend on validity of the padding, which makes it
+    /// suitable for decryption of messages received from untrusted sources.
+    ///
+    /// Returns none if padding is malformed or if input length is not multiple
+    /// of `Self::BlockSize`. Note that input length is not considered secret.
+    #[cfg(feature = "ct-padding")]
+    #[inline]
+    fn decrypt_padded_ct_inout<'out, P: CtPadding>(
+        mut self,
+        data: InOutBuf<'_, 'out, u8>,
+    ) -> CtOption<&'out [u8]> {
+        let (mut blocks, tail) = data.into_chunks();
+        if !tail.is_empty() {
+            return CtOption::none();
+        }
+        self.decrypt_blocks_inout(blocks.reborrow());
+        P::ct_unpad_blocks::<Self::BlockSize>(blocks.into_out())
+    }
+
+    /// Decrypt input and unpad it in-place in constant time. Returns resulting
+    /// plaintext slice.
+    ///
+    /// Returns none if padding is malformed or if input length is not multiple
+    /// of `Self::BlockSize`.
+    #[cfg(feature = "ct-padding")]
+    #[inline]
+    fn decrypt_padded_ct<P: CtPadding>(self, buf: &mut [u8]) -> CtOption<&[u8]> {
+        self.decrypt_padded_ct_inout::<P>(buf.into())
+    }
+
+    /// Decrypt input and unpad it buffer-to-buffer in constant time. Returns
+    /// resulting plaintext slice.
+    ///
+    /// Returns none if padding is malformed, if input length is not multiple
+    /// of `Self::BlockSize`, or if `out_buf` is smaller than `in_buf`.
+    #[cfg(feature = "ct-padding")]
+    #[inline]
+    fn decrypt_padded_ct_b2b<'a, P: CtPadding>(
+        self,
+        in_buf: &[u8],
+        out_buf: &'a mut [u8],
+    ) -> CtOption<&'a [u8]> {
+        let Some(out_buf) = out_buf.get_mut(..in_buf.len()) else {
+            return CtOption::none();
+        };
+        match InOutBuf::new(in_buf, out_buf) {
+            Ok(buf) => self.decrypt_padded_ct_inout::<P>(buf),
+            Err(_) => CtOption::none(),
+        }
+    }
+
+    /// Decrypt input and unpad it in constant time in a newly allocated Vec.
+    /// Returns resulting plaintext `Vec`.
+    ///
+    /// Returns none if padding is malformed or if input length is not multiple
+    /// of `Self::BlockSize`.
+    #[cfg(all(feature = "ct-padding", feature = "alloc"))]
+    #[inline]
+    fn decrypt_padded_ct_vec<P: CtPadding>(self, buf: &[u8]) -> CtOption<Vec<u8>> {
+        let mut out = vec![0; buf.len()];
+        let res = self.decrypt_padded_ct_b2b::<P>(buf, &mut out);
+        let is_some = res.is_some();
+        let len = res.map_or(0, <[u8]>::len);
+        out.truncate(len);
+        CtOption::new(out, is_some)
+    }
+
+    /// Get information about the backend used for decryption.
+    ///
+    /// # Panics
+    /// If implementation of `decrypt_with_backend` does not call the provided closure.
+    #[inline]
+    fn decrypt_backend_info(&mut self) -> BackendInfo {
+        let mut info = None;
+        self.decrypt_with_backend(BackendInfoCtx {
+            info: &mut info,
+            _pd: PhantomData,
+        });
+        info.expect("backend closure was not called")
+    }
 }
@@ -1,6 +1,41 @@
 use common::{Block, BlockSizeUser, ParBlocks, ParBlocksSizeUser, typenum::Unsigned};
 use inout::{InOut, InOutBuf};
 
+/// Information about a cipher backend.
+///
+/// Backends are often selected at runtime depending on available CPU features,
+/// so this information can be used to check whether hardware acceleration is used.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct BackendInfo {
+    /// Name of the backend, e.g. `"aesni"` or `"soft"`.
+    pub name: &'static str,
+    /// Number of blocks processed by the backend in parallel.
+    pub par_blocks: usize,
+    /// Whether execution time of the backend does not depend on processed data and keys.
+    pub constant_time: bool,
+}
+
+impl BackendInfo {
+    /// Create backend information from its parts.
+    #[must_use]
+    pub const fn new(name: &'static str, par_blocks: usize, constant_time: bool) -> Self {
+        Self {
+            name,
+            par_blocks,
+            constant_time,
+        }
+    }
+
+    /// Information reported by backends which do not override the `backend_info` method.
+    ///
+    /// It uses `"unknown"` as the backend name and does not claim constant-time execution.
+    #[must_use]
+    pub const fn unknown(par_blocks: usize) -> Self {
+        Self::new("unknown", par_blocks, false)
+    }
+}
+
 /// Trait implemented by block cipher mode encryption backends.
 pub trait BlockCipherEncBackend: ParBlocksSizeUser {
     /// Encrypt single inout block.
@@ -42,6 +77,12 @@ pub trait BlockCipherEncBackend: ParBlocksSizeUser {
     fn encrypt_tail_blocks_inplace(&self, blocks: &mut [Block<Self>]) {
         self.encrypt_tail_blocks(blocks.into());
     }
+
+    /// Get information about the backend.
+    #[inline(always)]
+    fn backend_info(&self) -> BackendInfo {
+        BackendInfo::unknown(Self::ParBlocksSize::USIZE)
+    }
 }
 
 /// Trait for [`BlockCipherEncBackend`] users.
@@ -93,6 +134,12 @@ pub trait BlockCipherDecBackend: ParBlocksSizeUser {
     fn decrypt_tail_blocks_inplace(&self, blocks: &mut [Block<Self>]) {
         self.decrypt_tail_blocks(blocks.into());
     }
+
+    /// Get information about the backend.
+    #[inline(always)]
+    fn backend_info(&self) -> BackendInfo {
+        BackendInfo::unknown(Self::ParBlocksSize::USIZE)
+    }
 }
 
 /// Trait for [`BlockCipherDecBackend`] users.
@@ -144,6 +191,12 @@ pub trait BlockModeEncBackend: ParBlocksSizeUser {
     fn encrypt_tail_blocks_inplace(&mut self, blocks: &mut [Block<Self>]) {
         self.encrypt_tail_blocks(blocks.into());
     }
+
+    /// Get information about the backend.
+    #[inline(always)]
+    fn backend_info(&self) -> BackendInfo {
+        BackendInfo::unknown(Self::ParBlocksSize::USIZE)
+    }
 }
 
 /// Trait for [`BlockModeEncBackend`] users.
@@ -195,6 +248,12 @@ pub trait BlockModeDecBackend: ParBlocksSizeUser {
     fn decrypt_tail_blocks_inplace(&mut self, blocks: &mut [Block<Self>]) {
         self.decrypt_tail_blocks(blocks.into());
     }
+
+    /// Get information about the backend.
+    #[inline(always)]
+    fn backend_info(&self) -> BackendInfo {
+        BackendInfo::unknown(Self::ParBlocksSize::USIZE)
+    }
 }
 
 /// Trait for [`BlockModeDecBackend`] users.
@@ -0,0 +1,74 @@
+use common::array::{Array, ArraySize};
+use ctutils::{Choice, CtAssign, CtEq, CtLt, CtOption, CtSelect};
+use inout::block_padding::{Iso7816, Padding, Pkcs7};
+
+/// Padding algorithms which support unpadding in constant time.
+///
+/// Unlike [`Padding::unpad_blocks`], methods of this trait do not branch on
+/// values of the padding bytes, i.e. execution time does not reveal whether
+/// padding was valid. This prevents [padding oracle] attacks against
+/// decryption of untrusted messages in modes like CBC.
+///
+/// [padding oracle]: https://en.wikipedia.org/wiki/Padding_oracle_attack
+pub trait CtPadding: Padding {
+    /// Get length of the data stored in the padded `block` in constant time.
+    ///
+    /// Returns none if padding is malformed.
+    fn ct_raw_unpad_len(block: &[u8]) -> CtOption<usize>;
+
+    /// Unpad data in `blocks` in constant time and return unpadded byte slice.
+    ///
+    /// Returns none if `blocks` are empty or contain malformed padding.
+    #[inline]
+    fn ct_unpad_blocks<BlockSize: ArraySize>(blocks: &[Array<u8, BlockSize>]) -> CtOption<&[u8]> {
+        let Some((last_block, full_blocks)) = blocks.split_last() else {
+            return CtOption::none();
+        };
+        let buf = Array::slice_as_flattened(blocks);
+        let full_len = full_blocks.len() * BlockSize::USIZE;
+        Self::ct_raw_unpad_len(last_block).map(|len| &buf[..full_len + len])
+    }
+}
+
+impl CtPadding for Pkcs7 {
+    /// # Panics
+    /// If `block` is empty or longer than 255 bytes.
+    #[inline]
+    fn ct_raw_unpad_len(block: &[u8]) -> CtOption<usize> {
+        assert!(
+            !block.is_empty() && block.len() <= 255,
+            "block size is not supported by PKCS#7",
+        );
+        let bs = block.len();
+        let n = block[bs - 1];
+        let pad_len = usize::from(n);
+
+        let mut is_valid = !n.ct_eq(&0) & !bs.ct_lt(&pad_len);
+        // `pos` is position of the byte counting from the end of the block
+        for (pos, b) in (1..).zip(block.iter().rev()) {
+            let is_pad = !pad_len.ct_lt(&pos);
+            is_valid &= !is_pad | b.ct_eq(&n);
+        }
+
+        let len = 0usize.ct_select(&bs.wrapping_sub(pad_len), is_valid);
+        CtOption::new(len, is_valid)
+    }
+}
+
+impl CtPadding for Iso7816 {
+    #[inline]
+    fn ct_raw_unpad_len(block: &[u8]) -> CtOption<usize> {
+        let mut found = Choice::FALSE;
+        let mut is_valid = Choice::TRUE;
+        let mut len = 0usize;
+        for (i, b) in block.iter().enumerate().rev() {
+            let is_marker = b.ct_eq(&0x80);
+            is_valid &= found | is_marker | b.ct_eq(&0);
+            let is_first_marker = !found & is_marker;
+            len.ct_assign(&i, is_first_marker);
+            found |= is_first_marker;
+        }
+        let is_valid = found & is_valid;
+        CtOption::new(0usize.ct_select(&len, is_valid), is_valid)
+    }
+}
@@ -1,9 +1,11 @@
 use common::{Block, BlockSizeUser, array::ArraySize, typenum::Unsigned};
+use core::marker::PhantomData;
 use inout::{InOut, InOutBuf};
 
 use super::{
-    BlockCipherDecBackend, BlockCipherDecClosure, BlockCipherEncBackend, BlockCipherEncClosure,
-    BlockModeDecBackend, BlockModeDecClosure, BlockModeEncBackend, BlockModeEncClosure,
+    BackendInfo, BlockCipherDecBackend, BlockCipherDecClosure, BlockCipherEncBackend,
+    BlockCipherEncClosure, BlockModeDecBackend, BlockModeDecClosure, BlockModeEncBackend,
+    BlockModeEncClosure,
 };
 
 /// Closure used in methods which operate over separate blocks.
@@ -118,3 +120,43 @@ impl<BS: ArraySize> BlockModeDecClosure for BlocksCtx<'_, '_, BS> {
         }
     }
 }
+
+/// Closure used to retrieve information about the backend.
+///
+/// It is also used by the stream cipher traits.
+pub(crate) struct BackendInfoCtx<'a, BS: ArraySize> {
+    pub info: &'a mut Option<BackendInfo>,
+    pub _pd: PhantomData<BS>,
+}
+
+impl<BS: ArraySize> BlockSizeUser for BackendInfoCtx<'_, BS> {
+    type BlockSize = BS;
+}
+
+impl<BS: ArraySize> BlockCipherEncClosure for BackendInfoCtx<'_, BS> {
+    #[inline(always)]
+    fn call<B: BlockCipherEncBackend<BlockSize = BS>>(self, backend: &B) {
+        *self.info = Some(backend.backend_info());
+    }
+}
+
+impl<BS: ArraySize> BlockCipherDecClosure for BackendInfoCtx<'_, BS> {
+    #[inline(always)]
+    fn call<B: BlockCipherDecBackend<BlockSize = BS>>(self, backend: &B) {
+        *self.info = Some(backend.backend_info());
+    }
+}
+
+impl<BS: ArraySize> BlockModeEncClosure for BackendInfoCtx<'_, BS> {
+    #[inline(always)]
+    fn call<B: BlockModeEncBackend<BlockSize = BS>>(self, backend: &mut B) {
+        *self.info = Some(backend.backend_info());
+    }
+}
+
+impl<BS: ArraySize> BlockModeDecClosure for BackendInfoCtx<'_, BS> {
+    #[inline(always)]
+    fn call<B: BlockModeDecBackend<BlockSize = BS>>(self, backend: &mut B) {
+        *self.info = Some(backend.backend_info());
+    }
+}
@@ -0,0 +1,273 @@
+use super::{BlockModeDecrypt, BlockModeEncrypt};
+use block_buffer::{BlockBuffer, BlockSizes, Lazy};
+use common::{
+    BlockSizeUser, Iv, IvSizeUser, Key, KeyInit, KeyIvInit, KeySizeUser, array::Array,
+    typenum::Unsigned,
+};
+use core::fmt;
+use inout::{
+    OutIsTooSmallError, PadError,
+    block_padding::{self, PaddedData, Padding},
+};
+#[cfg(feature = "zeroize")]
+use zeroize::ZeroizeOnDrop;
+
+/// Buffering wrapper around a block mode.
+///
+/// It allows to encrypt or decrypt data which arrives in chunks of arbitrary size
+/// without collecting the whole message in memory. Processed data is written
+/// into the user-provided output buffer in chunks which are multiple of the block size,
+/// see [`update_len`][Self::update_len] for the exact length of the written chunks.
+///
+/// The last received block is held back until more data is provided or until
+/// the buffer is finalized, so padding can be applied or removed.
+pub struct BlockModeBuffer<M>
+where
+    M: BlockSizeUser,
+    M::BlockSize: BlockSizes,
+{
+    mode: M,
+    buffer: BlockBuffer<M::BlockSize, Lazy>,
+}
+
+impl<M> Clone for BlockModeBuffer<M>
+where
+    M: BlockSizeUser + Clone,
+    M::BlockSize: BlockSizes,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            mode: self.mode.clone(),
+            buffer: self.buffer.clone(),
+        }
+    }
+}
+
+impl<M> fmt::Debug for BlockModeBuffer<M>
+where
+    M: BlockSizeUser + fmt::Debug,
+    M::BlockSize: BlockSizes,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BlockModeBuffer").finish_non_exhaustive()
+    }
+}
+
+impl<M> BlockModeBuffer<M>
+where
+    M: BlockSizeUser,
+    M::BlockSize: BlockSizes,
+{
+    /// Initialize from a block mode instance.
+    pub fn from_mode(mode: M) -> Self {
+        Self {
+            mode,
+            buffer: Default::default(),
+        }
+    }
+
+    /// Get reference to the wrapped block mode instance.
+    pub fn get_mode(&self) -> &M {
+        &self.mode
+    }
+
+    /// Get number of buffered bytes which were not yet processed by the block mode.
+    pub fn buffered_len(&self) -> usize {
+        self.buffer.get_pos()
+    }
+
+    /// Get length of output written by `encrypt_update` and `decrypt_update`
+    /// for input of `data_len` bytes.
+    ///
+    /// The last block of the processed data is always kept in the buffer,
+    /// so the result is less than `buffered_len() + data_len`.
+    pub fn update_len(&self, data_len: usize) -> usize {
+        update_len::<M::BlockSize>(self.buffer.get_pos(), data_len)
+    }
+}
+
+impl<M> BlockModeBuffer<M>
+where
+    M: BlockModeEncrypt,
+    M::BlockSize: BlockSizes,
+{
+    /// Encrypt `data` and write resulting ciphertext into `out`.
+    ///
+    /// Data which does not fill a whole block is buffered until the next call.
+    /// Length of the written ciphertext is equal to [`update_len`][Self::update_len].
+    ///
+    /// # Errors
+    /// Returns [`OutIsTooSmallError`] if `out` is shorter than the written ciphertext,
+    /// in which case state of the buffer is not modified.
+    #[inline]
+    pub fn encrypt_update<'a>(
+        &mut self,
+        data: &[u8],
+        out: &'a mut [u8],
+    ) -> Result<&'a [u8], OutIsTooSmallError> {
+        let Self { mode, buffer } = self;
+        process_update(buffer, data, out, |blocks| mode.encrypt_blocks(blocks))
+    }
+
+    /// Pad buffered data with padding algorithm `P`, encrypt it, and write
+    /// resulting ciphertext into `out`.
+    ///
+    /// Length of the written ciphertext is at most
+    /// [`buffered_len`][Self::buffered_len] rounded up to the next multiple of the block size.
+    ///
+    /// # Errors
+    /// Returns [`PadError`] if `NoPadding` is used and length of the processed
+    /// data is not multiple of the block size, or if `out` is not sufficiently long.
+    #[inline]
+    pub fn encrypt_finalize_padded<P: Padding>(
+        mut self,
+        out: &mut [u8],
+    ) -> Result<&[u8], PadError> {
+        let Self { mode, buffer } = &mut self;
+        let (blocks, tail_block) = match P::pad_detached(buffer.get_data()) {
+            PaddedData::Pad { blocks, tail_block } => (blocks, Some(tail_block)),
+            PaddedData::NoPad { blocks } => (blocks, None),
+            PaddedData::Error => return Err(PadError),
+        };
+        let n = blocks.len() + usize::from(tail_block.is_some());
+        let out = out.get_mut(..n * M::BlockSize::USIZE).ok_or(PadError)?;
+        let (out_blocks, _) = Array::slice_as_chunks_mut(out);
+        let (out_blocks, out_tail) = out_blocks.split_at_mut(blocks.len());
+        out_blocks.clone_from_slice(blocks);
+        mode.encrypt_blocks(out_blocks);
+        if let (Some(tail_block), [out_tail]) = (tail_block, out_tail) {
+            *out_tail = tail_block;
+            mode.encrypt_block(out_tail);
+        }
+        Ok(out)
+    }
+}
+
+impl<M> BlockModeBuffer<M>
+where
+    M: BlockModeDecrypt,
+    M::BlockSize: BlockSizes,
+{
+    /// Decrypt `data` and write resulting plaintext into `out`.
+    ///
+    /// Data which does not fill a whole block and the last full block are
+    /// buffered until the next call, so padding can be removed on finalization.
+    /// Length of the written plaintext is equal to [`update_len`][Self::update_len].
+    ///
+    /// # Errors
+    /// Returns [`OutIsTooSmallError`] if `out` is shorter than the written plaintext,
+    /// in which case state of the buffer is not modified.
+    #[inline]
+    pub fn decrypt_update<'a>(
+        &mut self,
+        data: &[u8],
+        out: &'a mut [u8],
+    ) -> Result<&'a [u8], OutIsTooSmallError> {
+        let Self { mode, buffer } = self;
+        process_update(buffer, data, out, |blocks| mode.decrypt_blocks(blocks))
+    }
+
+    /// Decrypt buffered data, unpad it with padding algorithm `P`, and write
+    /// resulting plaintext into `out`.
+    ///
+    /// Length of `out` must be at least [`buffered_len`][Self::buffered_len].
+    ///
+    /// # Errors
+    /// Returns [`block_padding::Error`] if padding is malformed, if length
+    /// of the processed data is not multiple of the block size, or if `out`
+    /// is not sufficiently long.
+    #[inline]
+    pub fn decrypt_finalize_padded<P: Padding>(
+        mut self,
+        out: &mut [u8],
+    ) -> Result<&[u8], block_padding::Error> {
+        let Self { mode, buffer } = &mut self;
+        let pos = buffer.get_pos();
+        if pos != 0 && pos != M::BlockSize::USIZE {
+            return Err(block_padding::Error);
+        }
+        let out = out.get_mut(..pos).ok_or(block_padding::Error)?;
+        out.copy_from_slice(buffer.get_data());
+        let (blocks, _) = Array::slice_as_chunks_mut(out);
+        mode.decrypt_blocks(blocks);
+        P::unpad_blocks(blocks)
+    }
+}
+
+/// Pass whole blocks from `buffer` and `data` to `f` after copying them into `out`,
+/// and return the processed part of `out`.
+#[inline(always)]
+fn process_update<'a, BS: BlockSizes>(
+    buffer: &mut BlockBuffer<BS, Lazy>,
+    data: &[u8],
+    out: &'a mut [u8],
+    mut f: impl FnMut(&mut [Array<u8, BS>]),
+) -> Result<&'a [u8], OutIsTooSmallError> {
+    let out = out
+        .get_mut(..update_len::<BS>(buffer.get_pos(), data.len()))
+        .ok_or(OutIsTooSmallError)?;
+    let (out_blocks, _) = Array::slice_as_chunks_mut(out);
+    let mut pos = 0;
+    buffer.digest_blocks(data, |blocks| {
+        let chunk = &mut out_blocks[pos..][..blocks.len()];
+        chunk.clone_from_slice(blocks);
+        f(chunk);
+        pos += blocks.len();
+    });
+    Ok(out)
+}
+
+/// Number of bytes processed by a lazy block buffer with `pos` buffered bytes
+/// after receiving `data_len` more bytes.
+#[inline(always)]
+fn update_len<BS: BlockSizes>(pos: usize, data_len: usize) -> usize {
+    let total = pos + data_len;
+    total.saturating_sub(1) / BS::USIZE * BS::USIZE
+}
+
+impl<M> KeySizeUser for BlockModeBuffer<M>
+where
+    M: KeySizeUser + BlockSizeUser,
+    M::BlockSize: BlockSizes,
+{
+    type KeySize = M::KeySize;
+}
+
+impl<M> IvSizeUser for BlockModeBuffer<M>
+where
+    M: IvSizeUser + BlockSizeUser,
+    M::BlockSize: BlockSizes,
+{
+    type IvSize = M::IvSize;
+}
+
+impl<M> KeyIvInit for BlockModeBuffer<M>
+where
+    M: KeyIvInit + BlockSizeUser,
+    M::BlockSize: BlockSizes,
+{
+    #[inline]
+    fn new(key: &Key<Self>, iv: &Iv<Self>) -> Self {
+        Self::from_mode(M::new(key, iv))
+    }
+}
+
+impl<M> KeyInit for BlockModeBuffer<M>
+where
+    M: KeyInit + BlockSizeUser,
+    M::BlockSize: BlockSizes,
+{
+    #[inline]
+    fn new(key: &Key<Self>) -> Self {
+        Self::from_mode(M::new(key))
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<M> ZeroizeOnDrop for BlockModeBuffer<M>
+where
+    M: BlockSizeUser + ZeroizeOnDrop,
+    M::BlockSize: BlockSizes,
+{
+}
@@ -0,0 +1,261 @@
+use super::{BackendInfo, BlockCipherEncrypt};
+use common::{
+    Block, BlockSizeUser, Key, KeyInit,
+    array::{Array, ArraySize},
+    typenum::Unsigned,
+};
+use core::{fmt, marker::PhantomData};
+use inout::{InOut, InOutBuf};
+
+/// Types which process blocks under several keys at once.
+pub trait KeyCountUser: BlockSizeUser {
+    /// Number of keys, i.e. number of blocks processed by one multi-key call.
+    type KeyCount: ArraySize;
+}
+
+/// Group of blocks where `i`-th block is processed under `i`-th key.
+pub type MultiKeyBlocks<T> = Array<Block<T>, <T as KeyCountUser>::KeyCount>;
+
+/// Trait implemented by multi-key block cipher encryption backends.
+///
+/// Such backends usually process blocks in separate SIMD lanes or use bitslicing.
+pub trait MultiKeyBlockCipherEncBackend: KeyCountUser {
+    /// Encrypt group of inout blocks, `i`-th block is encrypted under `i`-th key.
+    fn encrypt_multi_blocks(&self, blocks: InOut<'_, '_, MultiKeyBlocks<Self>>);
+
+    /// Encrypt group of blocks in-place.
+    #[inline(always)]
+    fn encrypt_multi_blocks_inplace(&self, blocks: &mut MultiKeyBlocks<Self>) {
+        self.encrypt_multi_blocks(blocks.into());
+    }
+
+    /// Get information about the backend.
+    #[inline(always)]
+    fn backend_info(&self) -> BackendInfo {
+        BackendInfo::unknown(Self::KeyCount::USIZE)
+    }
+}
+
+/// Trait for [`MultiKeyBlockCipherEncBackend`] users.
+///
+/// This trait is used to define rank-2 closures.
+pub trait MultiKeyBlockCipherEncClosure: KeyCountUser {
+    /// Execute closure with the provided multi-key block cipher backend.
+    fn call<B>(self, backend: &B)
+    where
+        B: MultiKeyBlockCipherEncBackend<BlockSize = Self::BlockSize, KeyCount = Self::KeyCount>;
+}
+
+/// Encrypt-only functionality for block ciphers which encrypt blocks under several
+/// different keys at once.
+///
+/// Generic implementation on top of [`BlockCipherEncrypt`] is provided by [`MultiKeyFallback`].
+pub trait MultiKeyBlockCipherEncrypt: KeyCountUser + Sized {
+    /// Encrypt data using backend provided to the rank-2 closure.
+    fn encrypt_multi_with_backend(
+        &self,
+        f: impl MultiKeyBlockCipherEncClosure<BlockSize = Self::BlockSize, KeyCount = Self::KeyCount>,
+    );
+
+    /// Encrypt group of `inout` blocks, `i`-th block is encrypted under `i`-th key.
+    #[inline]
+    fn encrypt_multi_blocks_inout(&self, blocks: InOut<'_, '_, MultiKeyBlocks<Self>>) {
+        self.encrypt_multi_with_backend(MultiBlocksCtx { blocks });
+    }
+
+    /// Encrypt group of blocks in-place, `i`-th block is encrypted under `i`-th key.
+    #[inline]
+    fn encrypt_multi_blocks(&self, blocks: &mut MultiKeyBlocks<Self>) {
+        let blocks = blocks.into();
+        self.encrypt_multi_with_backend(MultiBlocksCtx { blocks });
+    }
+
+    /// Encrypt group of blocks `in_blocks` and write result to `out_blocks`.
+    #[inline]
+    fn encrypt_multi_blocks_b2b(
+        &self,
+        in_blocks: &MultiKeyBlocks<Self>,
+        out_blocks: &mut MultiKeyBlocks<Self>,
+    ) {
+        let blocks = (in_blocks, out_blocks).into();
+        self.encrypt_multi_with_backend(MultiBlocksCtx { blocks });
+    }
+
+    /// Encrypt groups of blocks in-place, `i`-th block of each group is encrypted
+    /// under `i`-th key.
+    #[inline]
+    fn encrypt_multi_block_groups(&self, groups: &mut [MultiKeyBlocks<Self>]) {
+        let groups = groups.into();
+        self.encrypt_multi_with_backend(MultiGroupsCtx { groups });
+    }
+
+    /// Get information about the multi-key encryption backend.
+    ///
+    /// # Panics
+    /// If the backend closure is not called by the `encrypt_multi_with_backend` implementation.
+    #[inline]
+    fn encrypt_multi_backend_info(&self) -> BackendInfo {
+        let mut info = None;
+        self.encrypt_multi_with_backend(MultiBackendInfoCtx::<Self::BlockSize, Self::KeyCount> {
+            info: &mut info,
+            _pd: PhantomData,
+        });
+        info.expect("backend closure was not called")
+    }
+}
+
+/// Generic implementation of [`MultiKeyBlockCipherEncrypt`] which encrypts
+/// blocks one by one using `N` instances of block cipher `C`.
+pub struct MultiKeyFallback<C: BlockSizeUser, N: ArraySize> {
+    ciphers: Array<C, N>,
+}
+
+impl<C: BlockSizeUser, N: ArraySize> MultiKeyFallback<C, N> {
+    /// Initialize from block cipher instances.
+    pub fn from_ciphers(ciphers: Array<C, N>) -> Self {
+        Self { ciphers }
+    }
+
+    /// Get references to the wrapped block cipher instances.
+    pub fn get_ciphers(&self) -> &Array<C, N> {
+        &self.ciphers
+    }
+}
+
+impl<C: KeyInit + BlockSizeUser, N: ArraySize> MultiKeyFallback<C, N> {
+    /// Initialize from `N` keys.
+    pub fn new(keys: &Array<Key<C>, N>) -> Self {
+        Self::from_ciphers(Array::from_fn(|i| C::new(&keys[i])))
+    }
+}
+
+impl<C: BlockSizeUser, N: ArraySize> BlockSizeUser for MultiKeyFallback<C, N> {
+    type BlockSize = C::BlockSize;
+}
+
+impl<C: BlockSizeUser, N: ArraySize> KeyCountUser for MultiKeyFallback<C, N> {
+    type KeyCount = N;
+}
+
+impl<C: BlockCipherEncrypt, N: ArraySize> MultiKeyBlockCipherEncrypt for MultiKeyFallback<C, N> {
+    #[inline]
+    fn encrypt_multi_with_backend(
+        &self,
+        f: impl MultiKeyBlockCipherEncClosure<BlockSize = Self::BlockSize, KeyCount = N>,
+    ) {
+        f.call(self);
+    }
+}
+
+impl<C: BlockCipherEncrypt, N: ArraySize> MultiKeyBlockCipherEncBackend for MultiKeyFallback<C, N> {
+    #[inline]
+    fn encrypt_multi_blocks(&self, blocks: InOut<'_, '_, MultiKeyBlocks<Self>>) {
+        for (cipher, block) in self.ciphers.iter().zip(blocks.into_buf()) {
+            cipher.encrypt_block_inout(block);
+        }
+    }
+    #[inline]
+    fn backend_info(&self) -> BackendInfo {
+        self.ciphers
+            .first()
+            .map_or(BackendInfo::unknown(N::USIZE), |cipher| BackendInfo {
+                par_blocks: N::USIZE,
+                ..cipher.encrypt_backend_info()
+            })
+    }
+}
+
+impl<C: BlockSizeUser + Clone, N: ArraySize> Clone for MultiKeyFallback<C, N> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            ciphers: self.ciphers.clone(),
+        }
+    }
+}
+
+impl<C: BlockSizeUser + fmt::Debug, N: ArraySize> fmt::Debug for MultiKeyFallback<C, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MultiKeyFallback").finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<C, N> zeroize::ZeroizeOnDrop for MultiKeyFallback<C, N>
+where
+    C: BlockSizeUser + zeroize::ZeroizeOnDrop,
+    N: ArraySize,
+{
+}
+
+/// Closure used in methods which operate over a single group of blocks.
+struct MultiBlocksCtx<'inp, 'out, BS: ArraySize, N: ArraySize> {
+    blocks: InOut<'inp, 'out, Array<Array<u8, BS>, N>>,
+}
+
+impl<BS: ArraySize, N: ArraySize> BlockSizeUser for MultiBlocksCtx<'_, '_, BS, N> {
+    type BlockSize = BS;
+}
+
+impl<BS: ArraySize, N: ArraySize> KeyCountUser for MultiBlocksCtx<'_, '_, BS, N> {
+    type KeyCount = N;
+}
+
+impl<BS: ArraySize, N: ArraySize> MultiKeyBlockCipherEncClosure for MultiBlocksCtx<'_, '_, BS, N> {
+    #[inline(always)]
+    fn call<B>(self, backend: &B)
+    where
+        B: MultiKeyBlockCipherEncBackend<BlockSize = BS, KeyCount = N>,
+    {
+        backend.encrypt_multi_blocks(self.blocks);
+    }
+}
+
+/// Closure used in methods which operate over a slice of block groups.
+struct MultiGroupsCtx<'inp, 'out, BS: ArraySize, N: ArraySize> {
+    groups: InOutBuf<'inp, 'out, Array<Array<u8, BS>, N>>,
+}
+
+impl<BS: ArraySize, N: ArraySize> BlockSizeUser for MultiGroupsCtx<'_, '_, BS, N> {
+    type BlockSize = BS;
+}
+
+impl<BS: ArraySize, N: ArraySize> KeyCountUser for MultiGroupsCtx<'_, '_, BS, N> {
+    type KeyCount = N;
+}
+
+impl<BS: ArraySize, N: ArraySize> MultiKeyBlockCipherEncClosure for MultiGroupsCtx<'_, '_, BS, N> {
+    #[inline(always)]
+    fn call<B>(self, backend: &B)
+    where
+        B: MultiKeyBlockCipherEncBackend<BlockSize = BS, KeyCount = N>,
+    {
+        for blocks in self.groups {
+            backend.encrypt_multi_blocks(blocks);
+        }
+    }
+}
+
+/// Closure used to retrieve information about the backend.
+struct MultiBackendInfoCtx<'a, BS: ArraySize, N: ArraySize> {
+    info: &'a mut Option<BackendInfo>,
+    _pd: PhantomData<(BS, N)>,
+}
+
+impl<BS: ArraySize, N: ArraySize> BlockSizeUser for MultiBackendInfoCtx<'_, BS, N> {
+    type BlockSize = BS;
+}
+
+impl<BS: ArraySize, N: ArraySize> KeyCountUser for MultiBackendInfoCtx<'_, BS, N> {
+    type KeyCount = N;
+}
+
+impl<BS: ArraySize, N: ArraySize> MultiKeyBlockCipherEncClosure for MultiBackendInfoCtx<'_, BS, N> {
+    #[inline(always)]
+    fn call<B>(self, backend: &B)
+    where
+        B: MultiKeyBlockCipherEncBackend<BlockSize = BS, KeyCount = N>,
+    {
+        *self.info = Some(backend.backend_info());
+    }
+}
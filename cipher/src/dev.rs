@@ -2,6 +2,8 @@
 mod block_bench;
 pub mod block_cipher;
 pub mod block_mode;
+pub mod cavp;
+mod criterion_bench;
 pub mod stream;
 
 pub use blobby;
@@ -2,6 +2,8 @@
 
 #![allow(clippy::missing_errors_doc)]
 
+#[cfg(feature = "ct-padding")]
+use crate::{Array, Block, CtPadding};
 use crate::{BlockModeDecrypt, BlockModeEncrypt, KeyIvInit, inout::InOutBuf};
 
 const MAX_MSG_LEN: usize = 1 << 12;
@@ -93,6 +95,59 @@ pub fn decrypt<C: BlockModeDecrypt + KeyIvInit>(tv: &TestVector) -> Result<(), &
     Ok(())
 }
 
+/// Constant-time unpadding test.
+///
+/// Encrypts a two-block message with every possible single-byte modification of
+/// every valid padding of the last block and checks that the constant-time
+/// decryption methods accept exactly the same messages as their
+/// [`Padding`][crate::block_padding::Padding]-based counterparts and return
+/// the same plaintext.
+#[cfg(feature = "ct-padding")]
+pub fn padding_ct<C, P>(key: &[u8], iv: &[u8]) -> Result<(), &'static str>
+where
+    C: BlockModeEncrypt + BlockModeDecrypt + KeyIvInit,
+    P: CtPadding,
+{
+    let init = || C::new_from_slices(key, iv).map_err(|_| "cipher initialization failure");
+
+    let mut first_block = Block::<C>::default();
+    first_block.iter_mut().zip(0u8..).for_each(|(b, i)| *b = i);
+    let bs = first_block.len();
+
+    for pos in 0..bs {
+        let mut padded = Block::<C>::default();
+        padded.iter_mut().for_each(|b| *b = 0xA5);
+        P::raw_pad(&mut padded, pos);
+
+        for i in 0..bs {
+            for val in 0..=u8::MAX {
+                let mut blocks = [first_block.clone(), padded.clone()];
+                blocks[1][i] = val;
+                init()?.encrypt_blocks(&mut blocks);
+
+                let mut buf1 = blocks.clone();
+                let mut buf2 = blocks;
+                let buf1 = Array::slice_as_flattened_mut(&mut buf1);
+                let buf2 = Array::slice_as_flattened_mut(&mut buf2);
+                let expected = init()?.decrypt_padded::<P>(buf1).ok();
+                let res = init()?.decrypt_padded_ct::<P>(buf2).into_option();
+                if res != expected {
+                    return Err("constant-time unpadding result mismatch");
+                }
+            }
+        }
+    }
+
+    let mut buf = [0u8; 3];
+    if init()?.decrypt_padded_ct::<P>(&mut buf).is_some().into() {
+        return Err("constant-time unpadding accepted input with invalid length");
+    }
+    if init()?.decrypt_padded_ct::<P>(&mut []).is_some().into() {
+        return Err("constant-time unpadding accepted empty input");
+    }
+    Ok(())
+}
+
 /// Define block mode test
 #[macro_export]
 macro_rules! block_mode_test {
@@ -174,3 +229,25 @@ macro_rules! iv_state_test {
         }
     };
 }
+
+/// Define constant-time unpadding test
+#[cfg(feature = "ct-padding")]
+#[macro_export]
+macro_rules! padding_ct_test {
+    ($name:ident, $cipher:ty, $padding:ty $(,)?) => {
+        #[test]
+        fn $name() {
+            use cipher::*;
+
+            let mut key = Key::<$cipher>::default();
+            let mut iv = Iv::<$cipher>::default();
+            key.iter_mut().for_each(|b| *b = 0x42);
+            iv.iter_mut().for_each(|b| *b = 0x24);
+
+            let res = $crate::dev::block_mode::padding_ct::<$cipher, $padding>(&key, &iv);
+            if let Err(reason) = res {
+                panic!("constant-time padding test failed: {reason}");
+            }
+        }
+    };
+}
@@ -0,0 +1,538 @@
+//! Development-related functionality for [NIST CAVP][1] response files
+//!
+//! The `.rsp` files published by CAVP consist of sections (e.g. `[ENCRYPT]` and `[DECRYPT]`)
+//! which contain test records separated by blank lines. Each record is a list of
+//! `NAME = VALUE` lines. Lines starting with `#` are treated as comments.
+//!
+//! Runners defined in this module support the KAT and MMT files (e.g. `ECBGFSbox128.rsp`
+//! or `CBCMMT256.rsp`) and the Monte Carlo test files (e.g. `ECBMCT128.rsp`) defined in
+//! [AESAVS][2] for the ECB, CBC, CFB128, and OFB modes. Note that CBC, CFB128, and OFB share
+//! the same Monte Carlo procedure, so they are handled by the same runner.
+//!
+//! [1]: https://csrc.nist.gov/projects/cryptographic-algorithm-validation-program
+//! [2]: https://csrc.nist.gov/csrc/media/projects/cryptographic-algorithm-validation-program/documents/aes/aesavs.pdf
+
+#![allow(clippy::missing_errors_doc)]
+
+use crate::{
+    Block, BlockCipherDecrypt, BlockCipherEncrypt, BlockModeDecrypt, BlockModeEncrypt,
+    BlockSizeUser, Key, KeyInit, KeyIvInit, KeySizeUser, inout::InOutBuf,
+};
+use core::{fmt, mem};
+
+const MAX_MSG_LEN: usize = 1 << 12;
+const MCT_INNER_ITERATIONS: usize = 1000;
+
+/// Direction of a CAVP section.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Direction {
+    /// `[ENCRYPT]` section
+    Encrypt,
+    /// `[DECRYPT]` section
+    Decrypt,
+}
+
+impl Direction {
+    /// Names of the input and output fields used by records in this direction.
+    fn fields(self) -> (&'static str, &'static str) {
+        match self {
+            Self::Encrypt => ("PLAINTEXT", "CIPHERTEXT"),
+            Self::Decrypt => ("CIPHERTEXT", "PLAINTEXT"),
+        }
+    }
+}
+
+/// Error returned on parsing or test failure.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct CavpError {
+    /// Line number (starting from 1) of the malformed line or the first line
+    /// of the failed record.
+    pub line: usize,
+    /// Failure reason
+    pub reason: &'static str,
+}
+
+impl fmt::Display for CavpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.reason)
+    }
+}
+
+impl core::error::Error for CavpError {}
+
+/// Test record parsed from a CAVP response file.
+#[derive(Copy, Clone, Debug)]
+pub struct Record<'a> {
+    section: &'a str,
+    line: usize,
+    body: &'a str,
+}
+
+impl<'a> Record<'a> {
+    /// Name of the section containing this record, e.g. `ENCRYPT`.
+    ///
+    /// Returns empty string if the record is not preceded by a section header.
+    #[must_use]
+    pub fn section(&self) -> &'a str {
+        self.section
+    }
+
+    /// Direction of the section containing this record.
+    #[must_use]
+    pub fn direction(&self) -> Option<Direction> {
+        match self.section {
+            "ENCRYPT" => Some(Direction::Encrypt),
+            "DECRYPT" => Some(Direction::Decrypt),
+            _ => None,
+        }
+    }
+
+    /// Line number (starting from 1) of the first line of this record.
+    #[must_use]
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Iterate over `(name, value)` pairs of this record.
+    pub fn fields(&self) -> impl Iterator<Item = (&'a str, &'a str)> + use<'a> {
+        self.body.lines().filter_map(|line| {
+            let (name, value) = line.split_once('=')?;
+            Some((name.trim(), value.trim()))
+        })
+    }
+
+    /// Get value of the field with the given name.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&'a str> {
+        self.fields().find(|&(n, _)| n == name).map(|(_, v)| v)
+    }
+
+    /// Get value of the `COUNT` field.
+    #[must_use]
+    pub fn count(&self) -> Option<u64> {
+        self.get("COUNT")?.parse().ok()
+    }
+
+    /// Decode hex value of the field with the given name into `buf`.
+    ///
+    /// Returns slice of `buf` containing the decoded value.
+    pub fn get_hex<'b>(&self, name: &str, buf: &'b mut [u8]) -> Result<&'b mut [u8], &'static str> {
+        let value = self.get(name).ok_or("missing field")?;
+        decode_hex(value, buf)
+    }
+
+    /// Decode hex value of the field with the given name into `buf`
+    /// with length equal to `buf.len()`.
+    pub fn get_hex_exact(&self, name: &str, buf: &mut [u8]) -> Result<(), &'static str> {
+        let len = buf.len();
+        if self.get_hex(name, buf)?.len() != len {
+            return Err("unexpected field length");
+        }
+        Ok(())
+    }
+
+    fn error(&self, reason: &'static str) -> CavpError {
+        CavpError {
+            line: self.line,
+            reason,
+        }
+    }
+}
+
+/// Iterator over test records in a CAVP response file.
+#[derive(Clone, Debug)]
+pub struct Rsp<'a> {
+    src: &'a str,
+    pos: usize,
+    line: usize,
+    section: &'a str,
+}
+
+impl<'a> Rsp<'a> {
+    /// Create new iterator over records in the `.rsp` file contents.
+    #[must_use]
+    pub fn new(src: &'a str) -> Self {
+        Self {
+            src,
+            pos: 0,
+            line: 0,
+            section: "",
+        }
+    }
+
+    fn error(&mut self, reason: &'static str) -> CavpError {
+        // stop iteration after the first error
+        self.pos = self.src.len();
+        CavpError {
+            line: self.line,
+            reason,
+        }
+    }
+}
+
+impl<'a> Iterator for Rsp<'a> {
+    type Item = Result<Record<'a>, CavpError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // byte range and first line number of the current record
+        let mut record: Option<(usize, usize, usize)> = None;
+
+        while let Some(rest) = self.src.get(self.pos..).filter(|r| !r.is_empty()) {
+            let line_len = rest.find('\n').map_or(rest.len(), |i| i + 1);
+            let line = rest[..line_len].trim();
+            // leave section header which terminates the record for the next call
+            if line.starts_with('[') && record.is_some() {
+                break;
+            }
+            self.pos += line_len;
+            self.line += 1;
+
+            if line.is_empty() {
+                if record.is_some() {
+                    break;
+                }
+            } else if line.starts_with('#') {
+                continue;
+            } else if let Some(section) = line.strip_prefix('[') {
+                let Some(section) = section.strip_suffix(']') else {
+                    return Some(Err(self.error("malformed section header")));
+                };
+                self.section = section.trim();
+            } else if line.contains('=') {
+                match &mut record {
+                    Some((_, end, _)) => *end = self.pos,
+                    None => record = Some((self.pos - line_len, self.pos, self.line)),
+                }
+            } else {
+                return Some(Err(self.error("malformed record line")));
+            }
+        }
+
+        record.map(|(start, end, line)| {
+            Ok(Record {
+                section: self.section,
+                line,
+                body: &self.src[start..end],
+            })
+        })
+    }
+}
+
+/// Decode hex string into `buf` and return slice of `buf` containing the decoded value.
+pub fn decode_hex<'b>(hex: &str, buf: &'b mut [u8]) -> Result<&'b mut [u8], &'static str> {
+    fn nibble(c: u8) -> Result<u8, &'static str> {
+        match c {
+            b'0'..=b'9' => Ok(c - b'0'),
+            b'a'..=b'f' => Ok(c - b'a' + 10),
+            b'A'..=b'F' => Ok(c - b'A' + 10),
+            _ => Err("invalid hex character"),
+        }
+    }
+
+    let hex = hex.as_bytes();
+    if hex.len() % 2 != 0 {
+        return Err("odd hex string length");
+    }
+    let buf = buf
+        .get_mut(..hex.len() / 2)
+        .ok_or("decoded value does not fit into buffer")?;
+    for (b, pair) in buf.iter_mut().zip(hex.chunks_exact(2)) {
+        *b = (nibble(pair[0])? << 4) | nibble(pair[1])?;
+    }
+    Ok(buf)
+}
+
+/// Run `f` over all records in the given direction.
+fn run<'a>(
+    rsp: &'a str,
+    dir: Direction,
+    mut f: impl FnMut(&Record<'a>) -> Result<(), &'static str>,
+) -> Result<(), CavpError> {
+    let mut n = 0;
+    for record in Rsp::new(rsp) {
+        let record = record?;
+        if record.direction() == Some(dir) {
+            f(&record).map_err(|reason| record.error(reason))?;
+            n += 1;
+        }
+    }
+    if n == 0 {
+        return Err(CavpError {
+            line: 0,
+            reason: "no test records found",
+        });
+    }
+    Ok(())
+}
+
+/// Decode input and output fields of a KAT/MMT record into `buf`.
+fn kat_buffers<'b>(
+    record: &Record<'_>,
+    dir: Direction,
+    buf: &'b mut [u8; 2 * MAX_MSG_LEN],
+) -> Result<InOutBuf<'b, 'b, u8>, &'static str> {
+    let (in_name, out_name) = dir.fields();
+    let (input, expected) = buf.split_at_mut(MAX_MSG_LEN);
+    let len = record.get_hex(in_name, input)?.len();
+    if record.get_hex(out_name, expected)?.len() != len {
+        return Err("input/output length mismatch");
+    }
+    InOutBuf::new(&input[..len], &mut expected[..len]).map_err(|_| "input/output length mismatch")
+}
+
+/// Check that `out` is equal to the expected output of `record`.
+fn check_output(record: &Record<'_>, dir: Direction, out: &[u8]) -> Result<(), &'static str> {
+    let mut expected = [0u8; MAX_MSG_LEN];
+    let expected = record.get_hex(dir.fields().1, &mut expected)?;
+    if out != expected {
+        return Err(match dir {
+            Direction::Encrypt => "encryption failure",
+            Direction::Decrypt => "decryption failure",
+        });
+    }
+    Ok(())
+}
+
+fn ecb_kat_record<C: KeyInit + BlockSizeUser>(
+    record: &Record<'_>,
+    dir: Direction,
+    f: impl FnOnce(&C, InOutBuf<'_, '_, Block<C>>),
+) -> Result<(), &'static str> {
+    let mut key = Key::<C>::default();
+    record.get_hex_exact("KEY", &mut key)?;
+    let cipher = C::new(&key);
+
+    let mut buf = [0u8; 2 * MAX_MSG_LEN];
+    let mut buf = kat_buffers(record, dir, &mut buf)?;
+    let (blocks, tail) = buf.reborrow().into_chunks();
+    if !tail.is_empty() {
+        return Err("input length is not multiple of block size");
+    }
+    f(&cipher, blocks);
+    check_output(record, dir, buf.get_out())
+}
+
+/// Run KAT or MMT records from the `[ENCRYPT]` section of an ECB response file.
+pub fn ecb_kat_encrypt<C: BlockCipherEncrypt + KeyInit>(rsp: &str) -> Result<(), CavpError> {
+    let dir = Direction::Encrypt;
+    run(rsp, dir, |record| {
+        ecb_kat_record(record, dir, |cipher: &C, blocks| {
+            cipher.encrypt_blocks_inout(blocks);
+        })
+    })
+}
+
+/// Run KAT or MMT records from the `[DECRYPT]` section of an ECB response file.
+pub fn ecb_kat_decrypt<C: BlockCipherDecrypt + KeyInit>(rsp: &str) -> Result<(), CavpError> {
+    let dir = Direction::Decrypt;
+    run(rsp, dir, |record| {
+        ecb_kat_record(record, dir, |cipher: &C, blocks| {
+            cipher.decrypt_blocks_inout(blocks);
+        })
+    })
+}
+
+fn mode_kat_record<M: KeyIvInit + BlockSizeUser>(
+    record: &Record<'_>,
+    dir: Direction,
+    f: impl FnOnce(M, InOutBuf<'_, '_, Block<M>>),
+) -> Result<(), &'static str> {
+    let mut key = [0u8; 64];
+    let key = record.get_hex("KEY", &mut key)?;
+    let mut iv = [0u8; 64];
+    let iv = record.get_hex("IV", &mut iv)?;
+    let cipher = M::new_from_slices(key, iv).map_err(|_| "cipher initialization failure")?;
+
+    let mut buf = [0u8; 2 * MAX_MSG_LEN];
+    let mut buf = kat_buffers(record, dir, &mut buf)?;
+    let (blocks, tail) = buf.reborrow().into_chunks();
+    if !tail.is_empty() {
+        return Err("input length is not multiple of block size");
+    }
+    f(cipher, blocks);
+    check_output(record, dir, buf.get_out())
+}
+
+/// Run KAT or MMT records from the `[ENCRYPT]` section of a block mode response file.
+pub fn mode_kat_encrypt<M: BlockModeEncrypt + KeyIvInit>(rsp: &str) -> Result<(), CavpError> {
+    let dir = Direction::Encrypt;
+    run(rsp, dir, |record| {
+        mode_kat_record(record, dir, |mut cipher: M, blocks| {
+            cipher.encrypt_blocks_inout(blocks);
+        })
+    })
+}
+
+/// Run KAT or MMT records from the `[DECRYPT]` section of a block mode response file.
+pub fn mode_kat_decrypt<M: BlockModeDecrypt + KeyIvInit>(rsp: &str) -> Result<(), CavpError> {
+    let dir = Direction::Decrypt;
+    run(rsp, dir, |record| {
+        mode_kat_record(record, dir, |mut cipher: M, blocks| {
+            cipher.decrypt_blocks_inout(blocks);
+        })
+    })
+}
+
+/// Compute next Monte Carlo key by XORing `key` with the trailing bytes of `prev || cur`.
+fn mct_next_key(key: &mut [u8], prev: &[u8], cur: &[u8]) -> Result<(), &'static str> {
+    if key.len() > prev.len() + cur.len() {
+        return Err("key is bigger than two blocks");
+    }
+    let split = key.len().saturating_sub(cur.len());
+    let (key_prev, key_cur) = key.split_at_mut(split);
+    let cur = &cur[cur.len() - key_cur.len()..];
+    let prev = &prev[prev.len() - key_prev.len()..];
+    key_cur.iter_mut().zip(cur).for_each(|(a, b)| *a ^= b);
+    key_prev.iter_mut().zip(prev).for_each(|(a, b)| *a ^= b);
+    Ok(())
+}
+
+/// State of the Monte Carlo test chain expected in the next record.
+struct MctState<C: BlockSizeUser + KeySizeUser> {
+    key: Key<C>,
+    iv: Block<C>,
+    input: Block<C>,
+}
+
+fn mct_record<C>(
+    record: &Record<'_>,
+    dir: Direction,
+    state: &mut Option<MctState<C>>,
+    use_iv: bool,
+    f: impl FnOnce(&Key<C>, &Block<C>, &mut Block<C>, &mut Block<C>) -> Result<(), &'static str>,
+) -> Result<(), &'static str>
+where
+    C: BlockSizeUser + KeySizeUser,
+{
+    let (in_name, out_name) = dir.fields();
+    let mut key = Key::<C>::default();
+    record.get_hex_exact("KEY", &mut key)?;
+    let mut iv = Block::<C>::default();
+    if use_iv {
+        record.get_hex_exact("IV", &mut iv)?;
+    }
+    let mut input = Block::<C>::default();
+    record.get_hex_exact(in_name, &mut input)?;
+    let mut expected = Block::<C>::default();
+    record.get_hex_exact(out_name, &mut expected)?;
+
+    if record.count() == Some(0) {
+        *state = None;
+    }
+    if let Some(s) = state.take() {
+        if s.key != key || s.iv != iv || s.input != input {
+            return Err("Monte Carlo chaining failure");
+        }
+    }
+
+    // `prev` and `cur` contain the last two outputs of the inner loop
+    let mut prev = iv.clone();
+    let mut cur = input;
+    f(&key, &iv, &mut prev, &mut cur)?;
+    if cur != expected {
+        return Err(match dir {
+            Direction::Encrypt => "Monte Carlo encryption failure",
+            Direction::Decrypt => "Monte Carlo decryption failure",
+        });
+    }
+
+    mct_next_key(&mut key, &prev, &cur)?;
+    // ECB uses the last output as the next input, while the other modes
+    // use it as the next IV and use the second to last output as the next input
+    let (iv, input) = if use_iv { (cur, prev) } else { (iv, cur) };
+    *state = Some(MctState { key, iv, input });
+    Ok(())
+}
+
+/// Run the ECB Monte Carlo test defined in AESAVS over the ECB process function `f`.
+fn ecb_mct<C: KeyInit + BlockSizeUser>(
+    rsp: &str,
+    dir: Direction,
+    f: impl Fn(&C, &mut Block<C>),
+) -> Result<(), CavpError> {
+    let mut state = None;
+    run(rsp, dir, |record| {
+        mct_record::<C>(record, dir, &mut state, false, |key, _, prev, cur| {
+            let cipher = C::new(key);
+            for _ in 0..MCT_INNER_ITERATIONS {
+                prev.clone_from(cur);
+                f(&cipher, cur);
+            }
+            Ok(())
+        })
+    })
+}
+
+/// Run records from the `[ENCRYPT]` section of an ECB Monte Carlo response file.
+pub fn ecb_mct_encrypt<C: BlockCipherEncrypt + KeyInit>(rsp: &str) -> Result<(), CavpError> {
+    ecb_mct(rsp, Direction::Encrypt, |cipher: &C, block| {
+        cipher.encrypt_block(block);
+    })
+}
+
+/// Run records from the `[DECRYPT]` section of an ECB Monte Carlo response file.
+pub fn ecb_mct_decrypt<C: BlockCipherDecrypt + KeyInit>(rsp: &str) -> Result<(), CavpError> {
+    ecb_mct(rsp, Direction::Decrypt, |cipher: &C, block| {
+        cipher.decrypt_block(block);
+    })
+}
+
+/// Run the CBC/CFB128/OFB Monte Carlo test defined in AESAVS over the mode process function `f`.
+fn mode_mct<M: KeyIvInit + BlockSizeUser>(
+    rsp: &str,
+    dir: Direction,
+    f: impl Fn(&mut M, &mut Block<M>),
+) -> Result<(), CavpError> {
+    let mut state = None;
+    run(rsp, dir, |record| {
+        mct_record::<M>(record, dir, &mut state, true, |key, iv, prev, cur| {
+            let mut cipher =
+                M::new_from_slices(key, iv).map_err(|_| "cipher initialization failure")?;
+            // the first input of the next iteration is equal to IV and
+            // the following inputs are equal to the output of the previous iteration
+            for _ in 0..MCT_INNER_ITERATIONS {
+                f(&mut cipher, cur);
+                mem::swap(prev, cur);
+            }
+            mem::swap(prev, cur);
+            Ok(())
+        })
+    })
+}
+
+/// Run records from the `[ENCRYPT]` section of a CBC, CFB128, or OFB Monte Carlo response file.
+pub fn mode_mct_encrypt<M: BlockModeEncrypt + KeyIvInit>(rsp: &str) -> Result<(), CavpError> {
+    mode_mct(rsp, Direction::Encrypt, |cipher: &mut M, block| {
+        cipher.encrypt_block(block);
+    })
+}
+
+/// Run records from the `[DECRYPT]` section of a CBC, CFB128, or OFB Monte Carlo response file.
+pub fn mode_mct_decrypt<M: BlockModeDecrypt + KeyIvInit>(rsp: &str) -> Result<(), CavpError> {
+    mode_mct(rsp, Direction::Decrypt, |cipher: &mut M, block| {
+        cipher.decrypt_block(block);
+    })
+}
+
+/// Define CAVP response file test
+///
+/// `$test_fn` is one of the test functions from this module, e.g. [`ecb_kat_encrypt`],
+/// and `$file_name` is the name of the `.rsp` file inside the `data` directory
+/// without the extension.
+#[macro_export]
+macro_rules! cavp_test {
+    ($name:ident, $cipher:ty, $test_fn:ident, $file_name:expr $(,)?) => {
+        #[test]
+        fn $name() {
+            let rsp = include_str!(concat!("data/", $file_name, ".rsp"));
+            if let Err(err) = $crate::dev::cavp::$test_fn::<$cipher>(rsp) {
+                panic!(
+                    "\n\
+                    Failed CAVP test {}\n\
+                    {err}\n",
+                    $file_name,
+                );
+            }
+        }
+    };
+}
@@ -0,0 +1,208 @@
+//! `criterion`-based benchmarks for block and stream ciphers
+//!
+//! Stream cipher benchmarks measure throughput of in-place and buffer-to-buffer
+//! keystream application and of keystream generation across different message sizes.
+//!
+//! Block cipher and block mode benchmarks measure throughput of block-by-block (`block`),
+//! multi-block in-place (`blocks`), and multi-block buffer-to-buffer (`blocks-b2b`)
+//! processing across different message sizes. The `block` benchmark calls
+//! the single-block method for every block, while the other two pass the whole slice,
+//! which allows the backend to process `ParBlocksSize` blocks at once. The difference
+//! between them reflects gains from parallel block processing together with
+//! the per-call overhead. Backend methods are not benchmarked directly.
+
+/// Define `criterion`-based block encryptor benchmark
+#[macro_export]
+macro_rules! bench_block_encryptor {
+    (Key: $cipher:ty, $name:ident $(,)?) => {
+        $crate::bench_block_encryptor!(
+            {
+                let key = core::hint::black_box(Default::default());
+                <$cipher as $crate::KeyInit>::new(&key)
+            },
+            $cipher,
+            $name,
+        );
+    };
+    (KeyIv: $cipher:ty, $name:ident $(,)?) => {
+        $crate::bench_block_encryptor!(
+            {
+                let key = core::hint::black_box(Default::default());
+                let iv = core::hint::black_box(Default::default());
+                <$cipher as $crate::KeyIvInit>::new(&key, &iv)
+            },
+            $cipher,
+            $name,
+        );
+    };
+    ($init:block, $cipher:ty, $name:ident $(,)?) => {
+        #[doc = concat!("Benchmark encryption using `", stringify!($cipher), "`.")]
+        pub fn $name(c: &mut ::criterion::Criterion) {
+            use ::criterion::{BenchmarkId, Throughput};
+            use core::hint::black_box;
+            #[allow(unused)]
+            use $crate::{BlockCipherEncrypt, BlockModeEncrypt};
+
+            let mut group = c.benchmark_group(concat!(stringify!($cipher), " encrypt"));
+            for n in [1, 8, 64, 1024] {
+                #[allow(unused_mut)]
+                let mut cipher = $init;
+                let in_blocks: Vec<$crate::Block<$cipher>> = vec![Default::default(); n];
+                let mut blocks = in_blocks.clone();
+                let bytes = blocks.len() * blocks[0].len();
+                group.throughput(Throughput::Bytes(bytes as u64));
+
+                group.bench_function(BenchmarkId::new("block", bytes), |b| {
+                    b.iter(|| {
+                        for block in blocks.iter_mut() {
+                            cipher.encrypt_block(block);
+                        }
+                        black_box(&blocks);
+                    })
+                });
+                group.bench_function(BenchmarkId::new("blocks", bytes), |b| {
+                    b.iter(|| {
+                        cipher.encrypt_blocks(&mut blocks);
+                        black_box(&blocks);
+                    })
+                });
+                group.bench_function(BenchmarkId::new("blocks-b2b", bytes), |b| {
+                    b.iter(|| {
+                        cipher.encrypt_blocks_b2b(&in_blocks, &mut blocks).unwrap();
+                        black_box(&blocks);
+                    })
+                });
+            }
+            group.finish();
+        }
+    };
+}
+
+/// Define `criterion`-based block decryptor benchmark
+#[macro_export]
+macro_rules! bench_block_decryptor {
+    (Key: $cipher:ty, $name:ident $(,)?) => {
+        $crate::bench_block_decryptor!(
+            {
+                let key = core::hint::black_box(Default::default());
+                <$cipher as $crate::KeyInit>::new(&key)
+            },
+            $cipher,
+            $name,
+        );
+    };
+    (KeyIv: $cipher:ty, $name:ident $(,)?) => {
+        $crate::bench_block_decryptor!(
+            {
+                let key = core::hint::black_box(Default::default());
+                let iv = core::hint::black_box(Default::default());
+                <$cipher as $crate::KeyIvInit>::new(&key, &iv)
+            },
+            $cipher,
+            $name,
+        );
+    };
+    ($init:block, $cipher:ty, $name:ident $(,)?) => {
+        #[doc = concat!("Benchmark decryption using `", stringify!($cipher), "`.")]
+        pub fn $name(c: &mut ::criterion::Criterion) {
+            use ::criterion::{BenchmarkId, Throughput};
+            use core::hint::black_box;
+            #[allow(unused)]
+            use $crate::{BlockCipherDecrypt, BlockModeDecrypt};
+
+            let mut group = c.benchmark_group(concat!(stringify!($cipher), " decrypt"));
+            for n in [1, 8, 64, 1024] {
+                #[allow(unused_mut)]
+                let mut cipher = $init;
+                let in_blocks: Vec<$crate::Block<$cipher>> = vec![Default::default(); n];
+                let mut blocks = in_blocks.clone();
+                let bytes = blocks.len() * blocks[0].len();
+                group.throughput(Throughput::Bytes(bytes as u64));
+
+                group.bench_function(BenchmarkId::new("block", bytes), |b| {
+                    b.iter(|| {
+                        for block in blocks.iter_mut() {
+                            cipher.decrypt_block(block);
+                        }
+                        black_box(&blocks);
+                    })
+                });
+                group.bench_function(BenchmarkId::new("blocks", bytes), |b| {
+                    b.iter(|| {
+                        cipher.decrypt_blocks(&mut blocks);
+                        black_box(&blocks);
+                    })
+                });
+                group.bench_function(BenchmarkId::new("blocks-b2b", bytes), |b| {
+                    b.iter(|| {
+                        cipher.decrypt_blocks_b2b(&in_blocks, &mut blocks).unwrap();
+                        black_box(&blocks);
+                    })
+                });
+            }
+            group.finish();
+        }
+    };
+}
+
+/// Define `criterion`-based stream cipher benchmark
+#[macro_export]
+macro_rules! bench_stream_cipher {
+    (Key: $cipher:ty, $name:ident $(,)?) => {
+        $crate::bench_stream_cipher!(
+            {
+                let key = core::hint::black_box(Default::default());
+                <$cipher as $crate::KeyInit>::new(&key)
+            },
+            $cipher,
+            $name,
+        );
+    };
+    (KeyIv: $cipher:ty, $name:ident $(,)?) => {
+        $crate::bench_stream_cipher!(
+            {
+                let key = core::hint::black_box(Default::default());
+                let iv = core::hint::black_box(Default::default());
+                <$cipher as $crate::KeyIvInit>::new(&key, &iv)
+            },
+            $cipher,
+            $name,
+        );
+    };
+    ($init:block, $cipher:ty, $name:ident $(,)?) => {
+        #[doc = concat!("Benchmark keystream application using `", stringify!($cipher), "`.")]
+        pub fn $name(c: &mut ::criterion::Criterion) {
+            use ::criterion::{BenchmarkId, Throughput};
+            use core::hint::black_box;
+            use $crate::StreamCipher;
+
+            let mut group = c.benchmark_group(stringify!($cipher));
+            for len in [16, 256, 1024, 16384] {
+                let mut cipher = $init;
+                let input = vec![0u8; len];
+                let mut buf = input.clone();
+                group.throughput(Throughput::Bytes(len as u64));
+
+                group.bench_function(BenchmarkId::new("apply_keystream", len), |b| {
+                    b.iter(|| {
+                        cipher.apply_keystream(&mut buf);
+                        black_box(&buf);
+                    })
+                });
+                group.bench_function(BenchmarkId::new("apply_keystream-b2b", len), |b| {
+                    b.iter(|| {
+                        cipher.apply_keystream_b2b(&input, &mut buf);
+                        black_box(&buf);
+                    })
+                });
+                group.bench_function(BenchmarkId::new("write_keystream", len), |b| {
+                    b.iter(|| {
+                        cipher.write_keystream(&mut buf);
+                        black_box(&buf);
+                    })
+                });
+            }
+            group.finish();
+        }
+    };
+}
@@ -0,0 +1,178 @@
+//! Traits used to define functionality of [format-preserving encryption][1] (FPE) ciphers.
+//!
+//! # About format-preserving encryption
+//!
+//! FPE ciphers encrypt strings of numerals in a given radix into strings of numerals
+//! in the same radix and with the same length. For example, a 16-digit card number
+//! gets encrypted into another 16-digit decimal string. In addition to the key,
+//! encryption and decryption are parametrized by a public "tweak".
+//!
+//! Numerals are represented by `u16` values smaller than the domain radix.
+//!
+//! [1]: https://en.wikipedia.org/wiki/Format-preserving_encryption
+
+use core::fmt;
+
+#[cfg(feature = "alloc")]
+mod ff1;
+
+#[cfg(feature = "alloc")]
+pub use ff1::Ff1;
+
+/// Maximum radix supported by [`NumeralDomain`].
+pub const MAX_RADIX: u32 = 1 << 16;
+
+/// Domain of numeral strings on which FPE ciphers operate.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct NumeralDomain {
+    radix: u32,
+    min_len: usize,
+    max_len: usize,
+}
+
+impl NumeralDomain {
+    /// Create new numeral string domain with the given radix and inclusive length bounds.
+    ///
+    /// # Errors
+    /// Returns [`FpeError::InvalidRadix`] if `radix` is smaller than 2 or bigger
+    /// than [`MAX_RADIX`], and [`FpeError::InvalidLength`] if `min_len` is equal
+    /// to zero or bigger than `max_len`.
+    pub const fn new(radix: u32, min_len: usize, max_len: usize) -> Result<Self, FpeError> {
+        if radix < 2 || radix > MAX_RADIX {
+            return Err(FpeError::InvalidRadix);
+        }
+        if min_len == 0 || min_len > max_len {
+            return Err(FpeError::InvalidLength);
+        }
+        Ok(Self {
+            radix,
+            min_len,
+            max_len,
+        })
+    }
+
+    /// Radix of numerals.
+    #[must_use]
+    pub const fn radix(&self) -> u32 {
+        self.radix
+    }
+
+    /// Minimum length of numeral strings.
+    #[must_use]
+    pub const fn min_len(&self) -> usize {
+        self.min_len
+    }
+
+    /// Maximum length of numeral strings.
+    #[must_use]
+    pub const fn max_len(&self) -> usize {
+        self.max_len
+    }
+
+    /// Check that `numerals` belongs to the domain.
+    ///
+    /// # Errors
+    /// Returns [`FpeError::InvalidLength`] if length of `numerals` is outside of the domain
+    /// length bounds and [`FpeError::InvalidNumeral`] if one of the numerals is not smaller
+    /// than the domain radix.
+    pub fn check(&self, numerals: &[u16]) -> Result<(), FpeError> {
+        if numerals.len() < self.min_len || numerals.len() > self.max_len {
+            return Err(FpeError::InvalidLength);
+        }
+        if numerals.iter().any(|&x| u32::from(x) >= self.radix) {
+            return Err(FpeError::InvalidNumeral);
+        }
+        Ok(())
+    }
+}
+
+/// Trait implemented by FPE ciphers which exposes domain of supported numeral strings.
+pub trait FpeDomainUser {
+    /// Return domain of numeral strings supported by the cipher.
+    fn domain(&self) -> NumeralDomain;
+}
+
+/// Encrypt-only functionality for format-preserving encryption ciphers.
+pub trait FpeEncrypt: FpeDomainUser {
+    /// Encrypt numeral string in-place using the provided tweak.
+    ///
+    /// # Errors
+    /// Returns [`FpeError`] without modifying `numerals` if it does not belong to the cipher
+    /// domain or if `tweak` is not supported by the cipher.
+    fn encrypt_numerals(&self, tweak: &[u8], numerals: &mut [u16]) -> Result<(), FpeError>;
+
+    /// Encrypt numeral string buffer-to-buffer using the provided tweak.
+    ///
+    /// # Errors
+    /// Returns [`FpeError`] if `input` does not belong to the cipher domain, if `tweak`
+    /// is not supported by the cipher, or if `input` and `output` have different lengths.
+    #[inline]
+    fn encrypt_numerals_b2b(
+        &self,
+        tweak: &[u8],
+        input: &[u16],
+        output: &mut [u16],
+    ) -> Result<(), FpeError> {
+        if input.len() != output.len() {
+            return Err(FpeError::InvalidLength);
+        }
+        output.copy_from_slice(input);
+        self.encrypt_numerals(tweak, output)
+    }
+}
+
+/// Decrypt-only functionality for format-preserving encryption ciphers.
+pub trait FpeDecrypt: FpeDomainUser {
+    /// Decrypt numeral string in-place using the provided tweak.
+    ///
+    /// # Errors
+    /// Returns [`FpeError`] without modifying `numerals` if it does not belong to the cipher
+    /// domain or if `tweak` is not supported by the cipher.
+    fn decrypt_numerals(&self, tweak: &[u8], numerals: &mut [u16]) -> Result<(), FpeError>;
+
+    /// Decrypt numeral string buffer-to-buffer using the provided tweak.
+    ///
+    /// # Errors
+    /// Returns [`FpeError`] if `input` does not belong to the cipher domain, if `tweak`
+    /// is not supported by the cipher, or if `input` and `output` have different lengths.
+    #[inline]
+    fn decrypt_numerals_b2b(
+        &self,
+        tweak: &[u8],
+        input: &[u16],
+        output: &mut [u16],
+    ) -> Result<(), FpeError> {
+        if input.len() != output.len() {
+            return Err(FpeError::InvalidLength);
+        }
+        output.copy_from_slice(input);
+        self.decrypt_numerals(tweak, output)
+    }
+}
+
+/// Error type used by the format-preserving encryption traits.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum FpeError {
+    /// Radix is not supported.
+    InvalidRadix,
+    /// Length of numeral string is outside of the supported bounds.
+    InvalidLength,
+    /// Numeral is not smaller than radix.
+    InvalidNumeral,
+    /// Tweak is not supported.
+    InvalidTweak,
+}
+
+impl fmt::Display for FpeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.write_str(match self {
+            Self::InvalidRadix => "invalid radix",
+            Self::InvalidLength => "invalid numeral string length",
+            Self::InvalidNumeral => "invalid numeral",
+            Self::InvalidTweak => "invalid tweak",
+        })
+    }
+}
+
+impl core::error::Error for FpeError {}
@@ -0,0 +1,293 @@
+use super::{FpeDecrypt, FpeDomainUser, FpeEncrypt, FpeError, NumeralDomain};
+use crate::BlockCipherEncrypt;
+use alloc::{vec, vec::Vec};
+use common::{Block, BlockSizeUser, Key, KeyInit, typenum::U16};
+use core::fmt;
+
+/// Number of Feistel rounds used by FF1.
+const ROUNDS: u8 = 10;
+
+/// Generic implementation of the FF1 format-preserving encryption mode defined in
+/// [NIST SP 800-38G][1] over a block cipher with 128-bit block size.
+///
+/// FF1 requires that the domain radix is in the range of `[2, 2^16]`,
+/// that `radix^min_len >= 1_000_000`, and that `2 <= min_len <= max_len < 2^32`.
+///
+/// [1]: https://csrc.nist.gov/pubs/sp/800/38/g/upd1/final
+#[derive(Clone)]
+pub struct Ff1<C> {
+    cipher: C,
+    domain: NumeralDomain,
+}
+
+impl<C> Ff1<C>
+where
+    C: BlockCipherEncrypt + BlockSizeUser<BlockSize = U16>,
+{
+    /// Initialize FF1 with the given key and numeral string domain.
+    ///
+    /// # Errors
+    /// Returns [`FpeError`] if `domain` is not supported by FF1.
+    pub fn new(key: &Key<C>, domain: NumeralDomain) -> Result<Self, FpeError>
+    where
+        C: KeyInit,
+    {
+        Self::from_cipher(C::new(key), domain)
+    }
+
+    /// Initialize FF1 with the given block cipher instance and numeral string domain.
+    ///
+    /// # Errors
+    /// Returns [`FpeError`] if `domain` is not supported by FF1.
+    pub fn from_cipher(cipher: C, domain: NumeralDomain) -> Result<Self, FpeError> {
+        // the radix upper bound is enforced by `NumeralDomain`
+        let mut min_domain_size = 1u64;
+        for _ in 0..domain.min_len() {
+            min_domain_size = min_domain_size.saturating_mul(domain.radix().into());
+        }
+        if min_domain_size < 1_000_000 {
+            return Err(FpeError::InvalidLength);
+        }
+        if domain.min_len() < 2 || u32::try_from(domain.max_len()).is_err() {
+            return Err(FpeError::InvalidLength);
+        }
+        Ok(Self { cipher, domain })
+    }
+
+    /// Get reference to the wrapped block cipher instance.
+    #[must_use]
+    pub fn get_cipher(&self) -> &C {
+        &self.cipher
+    }
+
+    fn check(&self, tweak: &[u8], numerals: &[u16]) -> Result<(), FpeError> {
+        if u32::try_from(tweak.len()).is_err() {
+            return Err(FpeError::InvalidTweak);
+        }
+        self.domain.check(numerals)
+    }
+
+    /// Compute CBC-MAC of the `P` block which is common for all rounds.
+    #[allow(clippy::cast_possible_truncation)]
+    fn p_mac(&self, u: usize, n: usize, t: usize) -> Block<C> {
+        let radix = self.domain.radix().to_be_bytes();
+        // `n` and `t` are checked to fit into `u32` by `Self::check`
+        let n = (n as u32).to_be_bytes();
+        let t = (t as u32).to_be_bytes();
+        let mut p = Block::<C>::default();
+        p[..3].copy_from_slice(&[1, 2, 1]);
+        p[3..6].copy_from_slice(&radix[1..]);
+        p[6] = 10;
+        p[7] = u as u8;
+        p[8..12].copy_from_slice(&n);
+        p[12..].copy_from_slice(&t);
+        self.cipher.encrypt_block(&mut p);
+        p
+    }
+
+    /// Compute the round value `S` and write it into `s`.
+    fn round_value(&self, p_mac: &Block<C>, q: &[u8], s: &mut [u8]) {
+        let mut r = *p_mac;
+        for chunk in q.chunks_exact(16) {
+            r.iter_mut().zip(chunk).for_each(|(a, b)| *a ^= b);
+            self.cipher.encrypt_block(&mut r);
+        }
+
+        let mut chunks = s.chunks_mut(16);
+        if let Some(chunk) = chunks.next() {
+            chunk.copy_from_slice(&r[..chunk.len()]);
+        }
+        for (j, chunk) in (1u64..).zip(chunks) {
+            let mut block = r;
+            block[8..]
+                .iter_mut()
+                .zip(j.to_be_bytes())
+                .for_each(|(a, b)| *a ^= b);
+            self.cipher.encrypt_block(&mut block);
+            chunk.copy_from_slice(&block[..chunk.len()]);
+        }
+    }
+}
+
+/// Per-message FF1 parameters and scratch buffers.
+struct Rounds {
+    radix: u32,
+    u: usize,
+    v: usize,
+    /// Buffer for `Q`
+    q: Vec<u8>,
+    /// Length of `T || [0]^pad` prefix of `Q`
+    prefix_len: usize,
+    /// Buffer for `S` with length of `d` bytes
+    s: Vec<u8>,
+}
+
+impl Rounds {
+    fn new(radix: u32, tweak: &[u8], n: usize) -> Self {
+        let u = n / 2;
+        let v = n - u;
+        let b = byte_len(radix, v);
+        let d = 4 * b.div_ceil(4) + 4;
+        // `(-t - b - 1) mod 16`
+        let pad = (16 - (tweak.len() + b + 1) % 16) % 16;
+
+        let prefix_len = tweak.len() + pad;
+        let mut q = vec![0; prefix_len + 1 + b];
+        q[..tweak.len()].copy_from_slice(tweak);
+
+        Self {
+            radix,
+            u,
+            v,
+            q,
+            prefix_len,
+            s: vec![0; d],
+        }
+    }
+
+    fn m(&self, i: u8) -> usize {
+        if i % 2 == 0 { self.u } else { self.v }
+    }
+
+    /// Compute `Q` for round `i` and numeral string `x`.
+    ///
+    /// Returns `Q` and the buffer for `S`.
+    fn q(&mut self, i: u8, x: &[u16]) -> (&[u8], &mut [u8]) {
+        let (head, num) = self.q[self.prefix_len..].split_at_mut(1);
+        head[0] = i;
+        num_radix(x, self.radix, num);
+        (&self.q, &mut self.s)
+    }
+}
+
+impl<C> FpeDomainUser for Ff1<C> {
+    #[inline]
+    fn domain(&self) -> NumeralDomain {
+        self.domain
+    }
+}
+
+impl<C> FpeEncrypt for Ff1<C>
+where
+    C: BlockCipherEncrypt + BlockSizeUser<BlockSize = U16>,
+{
+    fn encrypt_numerals(&self, tweak: &[u8], x: &mut [u16]) -> Result<(), FpeError> {
+        self.check(tweak, x)?;
+        let radix = self.domain.radix();
+        let mut rounds = Rounds::new(radix, tweak, x.len());
+        let p_mac = self.p_mac(rounds.u, x.len(), tweak.len());
+
+        for i in 0..ROUNDS {
+            let m = rounds.m(i);
+            let (a, b) = x.split_at_mut(m);
+            let (q, s) = rounds.q(i, b);
+            self.round_value(&p_mac, q, s);
+            add_mod(a, s, radix);
+            // `C || B` -> `B || C`
+            x.rotate_left(m);
+        }
+        Ok(())
+    }
+}
+
+impl<C> FpeDecrypt for Ff1<C>
+where
+    C: BlockCipherEncrypt + BlockSizeUser<BlockSize = U16>,
+{
+    fn decrypt_numerals(&self, tweak: &[u8], x: &mut [u16]) -> Result<(), FpeError> {
+        self.check(tweak, x)?;
+        let radix = self.domain.radix();
+        let mut rounds = Rounds::new(radix, tweak, x.len());
+        let p_mac = self.p_mac(rounds.u, x.len(), tweak.len());
+
+        for i in (0..ROUNDS).rev() {
+            let m = rounds.m(i);
+            let (a, b) = x.split_at_mut(x.len() - m);
+            let (q, s) = rounds.q(i, a);
+            self.round_value(&p_mac, q, s);
+            sub_mod(b, s, radix);
+            // `A || C` -> `C || A`
+            x.rotate_right(m);
+        }
+        Ok(())
+    }
+}
+
+impl<C> fmt::Debug for Ff1<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Ff1")
+            .field("domain", &self.domain)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Compute number of bytes needed to represent `radix^v - 1`,
+/// i.e. `ceil(ceil(v * log2(radix)) / 8)`.
+fn byte_len(radix: u32, v: usize) -> usize {
+    if radix.is_power_of_two() {
+        let bits = radix.trailing_zeros() as usize;
+        return (v * bits).div_ceil(8);
+    }
+    // `radix` is not a power of two, so it's smaller than 2^16
+    let max_numeral = u16::try_from(radix - 1).expect("radix is smaller than 2^16");
+    let max_numerals = vec![max_numeral; v];
+    // numerals are smaller than 2^16, so `2 * v` bytes are always sufficient
+    let mut buf = vec![0u8; 2 * v];
+    num_radix(&max_numerals, radix, &mut buf);
+    let zeros = buf.iter().take_while(|&&b| b == 0).count();
+    buf.len() - zeros
+}
+
+/// Write `NUM_radix(x)` as a big-endian integer into `out`.
+#[allow(clippy::cast_possible_truncation)]
+fn num_radix(x: &[u16], radix: u32, out: &mut [u8]) {
+    out.fill(0);
+    for &numeral in x {
+        let mut carry = u32::from(numeral);
+        for byte in out.iter_mut().rev() {
+            let t = u32::from(*byte) * radix + carry;
+            *byte = t as u8;
+            carry = t >> 8;
+        }
+        debug_assert_eq!(carry, 0);
+    }
+}
+
+/// Divide big-endian integer `num` by `radix` in-place and return the remainder.
+#[allow(clippy::cast_possible_truncation)]
+fn div_rem(num: &mut [u8], radix: u32) -> u32 {
+    let mut rem = 0;
+    for byte in num {
+        let t = (rem << 8) | u32::from(*byte);
+        // `rem` is smaller than `radix`, so the quotient always fits into `u8`
+        *byte = (t / radix) as u8;
+        rem = t % radix;
+    }
+    rem
+}
+
+/// Compute `STR_radix((NUM_radix(x) + NUM(y)) mod radix^m)`, where `m` is equal to `x.len()`.
+///
+/// `y` is used as a scratch buffer.
+#[allow(clippy::cast_possible_truncation)]
+fn add_mod(x: &mut [u16], y: &mut [u8], radix: u32) {
+    let mut carry = 0;
+    for numeral in x.iter_mut().rev() {
+        let t = u32::from(*numeral) + div_rem(y, radix) + carry;
+        carry = u32::from(t >= radix);
+        *numeral = (t - carry * radix) as u16;
+    }
+}
+
+/// Compute `STR_radix((NUM_radix(x) - NUM(y)) mod radix^m)`, where `m` is equal to `x.len()`.
+///
+/// `y` is used as a scratch buffer.
+#[allow(clippy::cast_possible_truncation)]
+fn sub_mod(x: &mut [u16], y: &mut [u8], radix: u32) {
+    let mut borrow = 0;
+    for numeral in x.iter_mut().rev() {
+        let t = u32::from(*numeral) + radix - div_rem(y, radix) - borrow;
+        borrow = u32::from(t < radix);
+        *numeral = (t + borrow * radix - radix) as u16;
+    }
+}
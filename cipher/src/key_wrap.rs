@@ -0,0 +1,368 @@
+//! Generic implementation of the [RFC 3394] key wrap (KW) and the [RFC 5649] key wrap
+//! with padding (KWP) algorithms over block ciphers with 128-bit block size.
+//!
+//! The algorithms are also specified in [NIST SP 800-38F].
+//!
+//! [RFC 3394]: https://www.rfc-editor.org/rfc/rfc3394
+//! [RFC 5649]: https://www.rfc-editor.org/rfc/rfc5649
+//! [NIST SP 800-38F]: https://csrc.nist.gov/pubs/sp/800/38/f/final
+
+use crate::{BlockCipherDecrypt, BlockCipherEncrypt};
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+use common::{Block, BlockSizeUser, InvalidLength, Key, KeyInit, KeySizeUser, typenum::U16};
+use core::fmt;
+use ctutils::{Choice, CtEq};
+
+/// Size of the semiblock in bytes.
+const SEMIBLOCK_SIZE: usize = 8;
+
+/// Default initial value defined in RFC 3394.
+const KW_IV: [u8; SEMIBLOCK_SIZE] = [0xA6; SEMIBLOCK_SIZE];
+
+/// Prefix of the alternative initial value defined in RFC 5649.
+const KWP_IV_PREFIX: [u8; 4] = [0xA6, 0x59, 0x59, 0xA6];
+
+/// Key wrap over a block cipher with 128-bit block size.
+///
+/// Wrapped data is 8 bytes longer than the plaintext data for KW and
+/// up to 15 bytes longer for KWP.
+#[derive(Clone)]
+pub struct KeyWrap<C> {
+    cipher: C,
+}
+
+impl<C> KeyWrap<C> {
+    /// Initialize from a block cipher instance.
+    pub fn from_cipher(cipher: C) -> Self {
+        Self { cipher }
+    }
+
+    /// Get reference to the wrapped block cipher instance.
+    pub fn get_cipher(&self) -> &C {
+        &self.cipher
+    }
+}
+
+impl<C> KeyWrap<C>
+where
+    C: BlockCipherEncrypt + BlockSizeUser<BlockSize = U16>,
+{
+    /// Wrap `data` using the KW algorithm and write result into `out`.
+    /// Returns the resulting wrapped data slice.
+    ///
+    /// # Errors
+    /// Returns [`KeyWrapError::InvalidDataSize`] if length of `data` is not a multiple of 8
+    /// or smaller than 16 bytes, and [`KeyWrapError::InvalidOutputSize`] if `out`
+    /// is shorter than `data.len() + 8` bytes.
+    pub fn wrap<'a>(&self, data: &[u8], out: &'a mut [u8]) -> Result<&'a [u8], KeyWrapError> {
+        if data.len() % SEMIBLOCK_SIZE != 0 || data.len() < 2 * SEMIBLOCK_SIZE {
+            return Err(KeyWrapError::InvalidDataSize);
+        }
+        let out = out
+            .get_mut(..data.len() + SEMIBLOCK_SIZE)
+            .ok_or(KeyWrapError::InvalidOutputSize)?;
+
+        let (a, r) = out.split_at_mut(SEMIBLOCK_SIZE);
+        r.copy_from_slice(data);
+        a.copy_from_slice(&self.w(KW_IV, r));
+        Ok(out)
+    }
+
+    /// Wrap `data` using the KWP algorithm and write result into `out`.
+    /// Returns the resulting wrapped data slice.
+    ///
+    /// # Errors
+    /// Returns [`KeyWrapError::InvalidDataSize`] if `data` is empty or longer than `2^32 - 1`
+    /// bytes, and [`KeyWrapError::InvalidOutputSize`] if `out` is shorter than
+    /// [`KeyWrap::wrapped_with_padding_len`] bytes.
+    pub fn wrap_with_padding<'a>(
+        &self,
+        data: &[u8],
+        out: &'a mut [u8],
+    ) -> Result<&'a [u8], KeyWrapError> {
+        let mli = u32::try_from(data.len()).map_err(|_| KeyWrapError::InvalidDataSize)?;
+        if data.is_empty() {
+            return Err(KeyWrapError::InvalidDataSize);
+        }
+        let out = out
+            .get_mut(..Self::wrapped_with_padding_len(data.len()))
+            .ok_or(KeyWrapError::InvalidOutputSize)?;
+
+        let mut aiv = [0u8; SEMIBLOCK_SIZE];
+        aiv[..4].copy_from_slice(&KWP_IV_PREFIX);
+        aiv[4..].copy_from_slice(&mli.to_be_bytes());
+
+        let (a, r) = out.split_at_mut(SEMIBLOCK_SIZE);
+        r[..data.len()].copy_from_slice(data);
+        r[data.len()..].fill(0);
+
+        if r.len() == SEMIBLOCK_SIZE {
+            let mut block = Block::<C>::default();
+            block[..SEMIBLOCK_SIZE].copy_from_slice(&aiv);
+            block[SEMIBLOCK_SIZE..].copy_from_slice(r);
+            self.cipher.encrypt_block(&mut block);
+            out.copy_from_slice(&block);
+        } else {
+            a.copy_from_slice(&self.w(aiv, r));
+        }
+        Ok(out)
+    }
+
+    /// Wrap `data` using the KW algorithm into a newly allocated `Vec`.
+    ///
+    /// # Errors
+    /// Returns [`KeyWrapError::InvalidDataSize`] if length of `data` is not a multiple of 8
+    /// or smaller than 16 bytes.
+    #[cfg(feature = "alloc")]
+    pub fn wrap_vec(&self, data: &[u8]) -> Result<Vec<u8>, KeyWrapError> {
+        let mut out = vec![0; data.len() + SEMIBLOCK_SIZE];
+        self.wrap(data, &mut out)?;
+        Ok(out)
+    }
+
+    /// Wrap `data` using the KWP algorithm into a newly allocated `Vec`.
+    ///
+    /// # Errors
+    /// Returns [`KeyWrapError::InvalidDataSize`] if `data` is empty or longer than `2^32 - 1`
+    /// bytes.
+    #[cfg(feature = "alloc")]
+    pub fn wrap_with_padding_vec(&self, data: &[u8]) -> Result<Vec<u8>, KeyWrapError> {
+        let mut out = vec![0; Self::wrapped_with_padding_len(data.len())];
+        self.wrap_with_padding(data, &mut out)?;
+        Ok(out)
+    }
+
+    /// Return length of data wrapped using the KWP algorithm for `data_len` bytes of input.
+    #[must_use]
+    pub const fn wrapped_with_padding_len(data_len: usize) -> usize {
+        data_len.div_ceil(SEMIBLOCK_SIZE) * SEMIBLOCK_SIZE + SEMIBLOCK_SIZE
+    }
+
+    /// Wrapping function `W` defined in NIST SP 800-38F.
+    ///
+    /// Processes semiblocks `r` in-place and returns the resulting `A` value.
+    fn w(&self, iv: [u8; SEMIBLOCK_SIZE], r: &mut [u8]) -> [u8; SEMIBLOCK_SIZE] {
+        let n = r.len() / SEMIBLOCK_SIZE;
+        let mut block = Block::<C>::default();
+        block[..SEMIBLOCK_SIZE].copy_from_slice(&iv);
+
+        for j in 0..6 {
+            for (i, ri) in r.chunks_exact_mut(SEMIBLOCK_SIZE).enumerate() {
+                block[SEMIBLOCK_SIZE..].copy_from_slice(ri);
+                self.cipher.encrypt_block(&mut block);
+                let t = (n * j + i + 1) as u64;
+                xor_counter(&mut block, t);
+                ri.copy_from_slice(&block[SEMIBLOCK_SIZE..]);
+            }
+        }
+
+        let mut a = [0u8; SEMIBLOCK_SIZE];
+        a.copy_from_slice(&block[..SEMIBLOCK_SIZE]);
+        a
+    }
+}
+
+impl<C> KeyWrap<C>
+where
+    C: BlockCipherDecrypt + BlockSizeUser<BlockSize = U16>,
+{
+    /// Unwrap `data` using the KW algorithm and write result into `out`.
+    /// Returns the resulting unwrapped data slice.
+    ///
+    /// The integrity check is performed in constant time.
+    ///
+    /// # Errors
+    /// Returns [`KeyWrapError::InvalidDataSize`] if length of `data` is not a multiple of 8
+    /// or smaller than 24 bytes, [`KeyWrapError::InvalidOutputSize`] if `out` is shorter
+    /// than `data.len() - 8` bytes, and [`KeyWrapError::IntegrityCheckFailed`]
+    /// if the integrity check has failed. In the latter case the first `data.len() - 8`
+    /// bytes of `out` are filled with zeros.
+    pub fn unwrap<'a>(&self, data: &[u8], out: &'a mut [u8]) -> Result<&'a [u8], KeyWrapError> {
+        if data.len() % SEMIBLOCK_SIZE != 0 || data.len() < 3 * SEMIBLOCK_SIZE {
+            return Err(KeyWrapError::InvalidDataSize);
+        }
+        let out = out
+            .get_mut(..data.len() - SEMIBLOCK_SIZE)
+            .ok_or(KeyWrapError::InvalidOutputSize)?;
+
+        let (a, c) = data.split_at(SEMIBLOCK_SIZE);
+        out.copy_from_slice(c);
+        let a = self.w_inv(a, out);
+
+        if a.ct_eq(&KW_IV).to_bool() {
+            Ok(out)
+        } else {
+            out.fill(0);
+            Err(KeyWrapError::IntegrityCheckFailed)
+        }
+    }
+
+    /// Unwrap `data` using the KWP algorithm and write result into `out`.
+    /// Returns the resulting unwrapped data slice.
+    ///
+    /// The integrity check is performed in constant time.
+    ///
+    /// # Errors
+    /// Returns [`KeyWrapError::InvalidDataSize`] if length of `data` is not a multiple of 8
+    /// or smaller than 16 bytes, [`KeyWrapError::InvalidOutputSize`] if `out` is shorter
+    /// than `data.len() - 8` bytes, and [`KeyWrapError::IntegrityCheckFailed`]
+    /// if the integrity check has failed. In the latter case the first `data.len() - 8`
+    /// bytes of `out` are filled with zeros.
+    pub fn unwrap_with_padding<'a>(
+        &self,
+        data: &[u8],
+        out: &'a mut [u8],
+    ) -> Result<&'a [u8], KeyWrapError> {
+        if data.len() % SEMIBLOCK_SIZE != 0 || data.len() < 2 * SEMIBLOCK_SIZE {
+            return Err(KeyWrapError::InvalidDataSize);
+        }
+        let out = out
+            .get_mut(..data.len() - SEMIBLOCK_SIZE)
+            .ok_or(KeyWrapError::InvalidOutputSize)?;
+
+        let a = if data.len() == 2 * SEMIBLOCK_SIZE {
+            let mut block = Block::<C>::default();
+            block.copy_from_slice(data);
+            self.cipher.decrypt_block(&mut block);
+            out.copy_from_slice(&block[SEMIBLOCK_SIZE..]);
+            let mut a = [0u8; SEMIBLOCK_SIZE];
+            a.copy_from_slice(&block[..SEMIBLOCK_SIZE]);
+            a
+        } else {
+            let (a, c) = data.split_at(SEMIBLOCK_SIZE);
+            out.copy_from_slice(c);
+            self.w_inv(a, out)
+        };
+
+        let (prefix, mli) = a.split_at(4);
+        let mli = u64::from(u32::from_be_bytes([mli[0], mli[1], mli[2], mli[3]]));
+        let padded_len = out.len() as u64;
+
+        let mut is_valid = prefix.ct_eq(&KWP_IV_PREFIX);
+        is_valid &= Choice::from_u64_lt(padded_len - SEMIBLOCK_SIZE as u64, mli);
+        is_valid &= Choice::from_u64_le(mli, padded_len);
+
+        // check that padding bytes are equal to zero
+        let last_semiblock = &out[out.len() - SEMIBLOCK_SIZE..];
+        let mut pad_acc = 0u8;
+        for (idx, b) in (padded_len - SEMIBLOCK_SIZE as u64..).zip(last_semiblock) {
+            pad_acc |= b & Choice::from_u64_le(mli, idx).to_u8_mask();
+        }
+        is_valid &= Choice::from_u8_eq(pad_acc, 0);
+
+        match usize::try_from(mli) {
+            Ok(len) if is_valid.to_bool() => Ok(&out[..len]),
+            _ => {
+                out.fill(0);
+                Err(KeyWrapError::IntegrityCheckFailed)
+            }
+        }
+    }
+
+    /// Unwrap `data` using the KW algorithm into a newly allocated `Vec`.
+    ///
+    /// # Errors
+    /// Returns [`KeyWrapError::InvalidDataSize`] if length of `data` is not a multiple of 8
+    /// or smaller than 24 bytes, and [`KeyWrapError::IntegrityCheckFailed`]
+    /// if the integrity check has failed.
+    #[cfg(feature = "alloc")]
+    pub fn unwrap_vec(&self, data: &[u8]) -> Result<Vec<u8>, KeyWrapError> {
+        let mut out = vec![0; data.len().saturating_sub(SEMIBLOCK_SIZE)];
+        self.unwrap(data, &mut out)?;
+        Ok(out)
+    }
+
+    /// Unwrap `data` using the KWP algorithm into a newly allocated `Vec`.
+    ///
+    /// # Errors
+    /// Returns [`KeyWrapError::InvalidDataSize`] if length of `data` is not a multiple of 8
+    /// or smaller than 16 bytes, and [`KeyWrapError::IntegrityCheckFailed`]
+    /// if the integrity check has failed.
+    #[cfg(feature = "alloc")]
+    pub fn unwrap_with_padding_vec(&self, data: &[u8]) -> Result<Vec<u8>, KeyWrapError> {
+        let mut out = vec![0; data.len().saturating_sub(SEMIBLOCK_SIZE)];
+        let len = self.unwrap_with_padding(data, &mut out)?.len();
+        out.truncate(len);
+        Ok(out)
+    }
+
+    /// Unwrapping function `W^-1` defined in NIST SP 800-38F.
+    ///
+    /// Processes semiblocks `r` in-place and returns the resulting `A` value.
+    fn w_inv(&self, a: &[u8], r: &mut [u8]) -> [u8; SEMIBLOCK_SIZE] {
+        let n = r.len() / SEMIBLOCK_SIZE;
+        let mut block = Block::<C>::default();
+        block[..SEMIBLOCK_SIZE].copy_from_slice(a);
+
+        for j in (0..6).rev() {
+            for (i, ri) in r.chunks_exact_mut(SEMIBLOCK_SIZE).enumerate().rev() {
+                let t = (n * j + i + 1) as u64;
+                xor_counter(&mut block, t);
+                block[SEMIBLOCK_SIZE..].copy_from_slice(ri);
+                self.cipher.decrypt_block(&mut block);
+                ri.copy_from_slice(&block[SEMIBLOCK_SIZE..]);
+            }
+        }
+
+        let mut a = [0u8; SEMIBLOCK_SIZE];
+        a.copy_from_slice(&block[..SEMIBLOCK_SIZE]);
+        a
+    }
+}
+
+/// XOR counter `t` into the `A` part of `block`.
+fn xor_counter(block: &mut [u8], t: u64) {
+    block[..SEMIBLOCK_SIZE]
+        .iter_mut()
+        .zip(t.to_be_bytes())
+        .for_each(|(a, b)| *a ^= b);
+}
+
+impl<C: KeySizeUser> KeySizeUser for KeyWrap<C> {
+    type KeySize = C::KeySize;
+}
+
+impl<C: KeyInit> KeyInit for KeyWrap<C> {
+    #[inline]
+    fn new(key: &Key<Self>) -> Self {
+        Self {
+            cipher: C::new(key),
+        }
+    }
+
+    #[inline]
+    fn new_from_slice(key: &[u8]) -> Result<Self, InvalidLength> {
+        C::new_from_slice(key).map(|cipher| Self { cipher })
+    }
+}
+
+impl<C> fmt::Debug for KeyWrap<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyWrap").finish_non_exhaustive()
+    }
+}
+
+/// Error type used by [`KeyWrap`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum KeyWrapError {
+    /// Input data length is not supported by the algorithm.
+    InvalidDataSize,
+    /// Output buffer is too small.
+    InvalidOutputSize,
+    /// Integrity check of unwrapped data has failed.
+    IntegrityCheckFailed,
+}
+
+impl fmt::Display for KeyWrapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.write_str(match self {
+            Self::InvalidDataSize => "invalid data size",
+            Self::InvalidOutputSize => "invalid output buffer size",
+            Self::IntegrityCheckFailed => "integrity check failed",
+        })
+    }
+}
+
+impl core::error::Error for KeyWrapError {}
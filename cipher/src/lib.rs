@@ -15,6 +15,10 @@ pub use blobby;
 pub use common;
 #[cfg(feature = "rand_core")]
 pub use common::rand_core;
+#[cfg(feature = "ct-padding")]
+pub use ctutils;
+#[cfg(feature = "mac")]
+pub use digest;
 pub use inout;
 #[cfg(feature = "block-padding")]
 pub use inout::block_padding;
@@ -24,6 +28,11 @@ pub use zeroize;
 pub mod block;
 #[cfg(feature = "dev")]
 pub mod dev;
+pub mod fpe;
+#[cfg(feature = "key-wrap")]
+pub mod key_wrap;
+#[cfg(feature = "mac")]
+pub mod mac;
 pub mod stream;
 pub mod tweak;
 
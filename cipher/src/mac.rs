@@ -0,0 +1,85 @@
+//! Generic block cipher based message authentication codes.
+//!
+//! This module provides [CMAC] and [PMAC] implemented over any block cipher
+//! which implements [`BlockCipherEncrypt`]. The MAC types implement the [`digest::Mac`]
+//! trait. Supported block sizes are 64, 128, 256, 512, and 1024 bits.
+//!
+//! [CMAC]: https://en.wikipedia.org/wiki/One-key_MAC
+//! [PMAC]: https://en.wikipedia.org/wiki/PMAC_(cryptography)
+
+use crate::BlockCipherEncrypt;
+use common::{BlockSizeUser, typenum::Unsigned};
+use digest::block_api::SmallBlockSizeUser;
+
+mod cmac;
+mod pmac;
+
+pub use cmac::{Cmac, CmacCore};
+pub use digest::Mac;
+pub use pmac::{Pmac, PmacCore};
+
+/// Block ciphers which can be used with [`Cmac`] and [`Pmac`].
+pub trait MacCipher: BlockCipherEncrypt + SmallBlockSizeUser + Clone {}
+
+impl<C: BlockCipherEncrypt + SmallBlockSizeUser + Clone> MacCipher for C {}
+
+/// Get the lexicographically first irreducible polynomial with the minimum
+/// number of non-zero coefficients used for doubling of blocks with the given size.
+const fn dbl_poly(block_size: usize) -> u32 {
+    match block_size {
+        8 => 0x1B,
+        16 => 0x87,
+        32 => 0x425,
+        64 => 0x125,
+        128 => 0x8_0043,
+        _ => panic!("unsupported block size"),
+    }
+}
+
+/// Helper used to check block size support at compile time.
+struct Poly<C>(C);
+
+impl<C: BlockSizeUser> Poly<C> {
+    const R: u32 = dbl_poly(C::BlockSize::USIZE);
+}
+
+/// Multiply big-endian `block` by `x` in `GF(2^n)`.
+fn dbl<C: BlockSizeUser>(block: &mut [u8]) {
+    let msb = block[0] >> 7;
+    let mut carry = 0;
+    for b in block.iter_mut().rev() {
+        let next_carry = *b >> 7;
+        *b = (*b << 1) | carry;
+        carry = next_carry;
+    }
+    xor_poly(block, Poly::<C>::R, msb);
+}
+
+/// Multiply big-endian `block` by `x^-1` in `GF(2^n)`.
+fn inv_dbl<C: BlockSizeUser>(block: &mut [u8]) {
+    let lsb = block[block.len() - 1] & 1;
+    // the polynomial is odd, so after XORing it the last bit is equal to zero
+    xor_poly(block, Poly::<C>::R, lsb);
+    let mut carry = lsb;
+    for b in block.iter_mut() {
+        let next_carry = *b & 1;
+        *b = (*b >> 1) | (carry << 7);
+        carry = next_carry;
+    }
+}
+
+/// XOR `poly` into the trailing bytes of `block` if `bit` is equal to 1.
+fn xor_poly(block: &mut [u8], poly: u32, bit: u8) {
+    let mask = 0u32.wrapping_sub(bit.into());
+    let poly = (poly & mask).to_be_bytes();
+    let n = block.len();
+    block[n - poly.len()..]
+        .iter_mut()
+        .zip(poly)
+        .for_each(|(a, b)| *a ^= b);
+}
+
+#[inline(always)]
+fn xor(a: &mut [u8], b: &[u8]) {
+    a.iter_mut().zip(b).for_each(|(a, b)| *a ^= b);
+}
@@ -0,0 +1,123 @@
+use super::{MacCipher, dbl, xor};
+use crate::Block;
+use common::{AlgorithmName, BlockSizeUser, InnerInit, InnerUser, OutputSizeUser};
+use core::fmt;
+use digest::{
+    MacMarker, Output, Reset,
+    block_api::{Buffer, BufferKindUser, FixedOutputCore, Lazy, UpdateCore},
+};
+
+#[cfg(feature = "zeroize")]
+use zeroize::Zeroize;
+
+digest::buffer_fixed!(
+    /// Generic [CMAC] instance defined in [NIST SP 800-38B][1] and [RFC 4493][2].
+    ///
+    /// [CMAC]: https://en.wikipedia.org/wiki/One-key_MAC
+    /// [1]: https://csrc.nist.gov/pubs/sp/800/38/b/upd1/final
+    /// [2]: https://www.rfc-editor.org/rfc/rfc4493
+    pub struct Cmac<C: MacCipher>(CmacCore<C>);
+    impl: ResetMacTraits InnerInit Clone;
+);
+
+impl<C: MacCipher + AlgorithmName> AlgorithmName for Cmac<C> {
+    fn write_alg_name(f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        CmacCore::<C>::write_alg_name(f)
+    }
+}
+
+/// Block-level core of [`Cmac`].
+#[derive(Clone)]
+pub struct CmacCore<C: MacCipher> {
+    cipher: C,
+    state: Block<C>,
+}
+
+impl<C: MacCipher> BlockSizeUser for CmacCore<C> {
+    type BlockSize = C::BlockSize;
+}
+
+impl<C: MacCipher> OutputSizeUser for CmacCore<C> {
+    type OutputSize = C::BlockSize;
+}
+
+impl<C: MacCipher> InnerUser for CmacCore<C> {
+    type Inner = C;
+}
+
+impl<C: MacCipher> InnerInit for CmacCore<C> {
+    #[inline]
+    fn inner_init(cipher: C) -> Self {
+        Self {
+            cipher,
+            state: Default::default(),
+        }
+    }
+}
+
+impl<C: MacCipher> BufferKindUser for CmacCore<C> {
+    // the last block has to be processed by `finalize_fixed_core`
+    type BufferKind = Lazy;
+}
+
+impl<C: MacCipher> UpdateCore for CmacCore<C> {
+    #[inline]
+    fn update_blocks(&mut self, blocks: &[Block<Self>]) {
+        for block in blocks {
+            xor(&mut self.state, block);
+            self.cipher.encrypt_block(&mut self.state);
+        }
+    }
+}
+
+impl<C: MacCipher> FixedOutputCore for CmacCore<C> {
+    #[inline]
+    fn finalize_fixed_core(&mut self, buffer: &mut Buffer<Self>, out: &mut Output<Self>) {
+        let pos = buffer.get_pos();
+        let mut last_block = buffer.pad_with_zeros();
+
+        // derive subkeys `K1` and `K2`
+        let mut subkey = Block::<C>::default();
+        self.cipher.encrypt_block(&mut subkey);
+        dbl::<C>(&mut subkey);
+        if pos < last_block.len() {
+            last_block[pos] = 0x80;
+            dbl::<C>(&mut subkey);
+        }
+
+        xor(&mut self.state, &last_block);
+        xor(&mut self.state, &subkey);
+        self.cipher.encrypt_block(&mut self.state);
+        out.copy_from_slice(&self.state);
+    }
+}
+
+impl<C: MacCipher> MacMarker for CmacCore<C> {}
+
+impl<C: MacCipher> Reset for CmacCore<C> {
+    #[inline]
+    fn reset(&mut self) {
+        self.state = Default::default();
+    }
+}
+
+impl<C: MacCipher + AlgorithmName> AlgorithmName for CmacCore<C> {
+    fn write_alg_name(f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Cmac<")?;
+        C::write_alg_name(f)?;
+        f.write_str(">")
+    }
+}
+
+impl<C: MacCipher> fmt::Debug for CmacCore<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("CmacCore { ... }")
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<C: MacCipher> Drop for CmacCore<C> {
+    fn drop(&mut self) {
+        self.state.zeroize();
+    }
+}
@@ -0,0 +1,214 @@
+use super::{MacCipher, dbl, inv_dbl, xor};
+use crate::{Block, BlockCipherEncBackend, BlockCipherEncClosure, ParBlocks};
+use common::{
+    AlgorithmName, BlockSizeUser, InnerInit, InnerUser, OutputSizeUser, typenum::Unsigned,
+};
+use core::fmt;
+use digest::{
+    MacMarker, Output, Reset,
+    block_api::{Buffer, BufferKindUser, FixedOutputCore, Lazy, UpdateCore},
+};
+
+#[cfg(feature = "zeroize")]
+use zeroize::Zeroize;
+
+/// Number of cached `L(i)` values.
+const LC_SIZE: usize = 20;
+
+digest::buffer_fixed!(
+    /// Generic [PMAC] instance.
+    ///
+    /// This type implements the PMAC1 variant of the construction. Message blocks
+    /// are processed using parallel block encryption provided by the cipher backend.
+    ///
+    /// [PMAC]: https://web.cs.ucdavis.edu/~rogaway/ocb/pmac.htm
+    pub struct Pmac<C: MacCipher>(PmacCore<C>);
+    impl: ResetMacTraits InnerInit Clone;
+);
+
+impl<C: MacCipher + AlgorithmName> AlgorithmName for Pmac<C> {
+    fn write_alg_name(f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        PmacCore::<C>::write_alg_name(f)
+    }
+}
+
+/// Block-level core of [`Pmac`].
+#[derive(Clone)]
+pub struct PmacCore<C: MacCipher> {
+    cipher: C,
+    state: PmacState<C>,
+}
+
+#[derive(Clone)]
+struct PmacState<C: MacCipher> {
+    /// Cached `L(i) = L * x^i` values
+    l_cache: [Block<C>; LC_SIZE],
+    /// `L * x^-1`
+    l_inv: Block<C>,
+    offset: Block<C>,
+    sigma: Block<C>,
+    /// Number of processed blocks
+    counter: u64,
+}
+
+impl<C: MacCipher> PmacState<C> {
+    /// Update offset and return input block for the block cipher.
+    #[inline(always)]
+    fn next_input(&mut self, block: &Block<C>) -> Block<C> {
+        self.counter += 1;
+        let ntz = self.counter.trailing_zeros() as usize;
+        if let Some(l) = self.l_cache.get(ntz) {
+            xor(&mut self.offset, l);
+        } else {
+            let mut l = self.l_cache[LC_SIZE - 1].clone();
+            for _ in LC_SIZE - 1..ntz {
+                dbl::<C>(&mut l);
+            }
+            xor(&mut self.offset, &l);
+        }
+        let mut res = block.clone();
+        xor(&mut res, &self.offset);
+        res
+    }
+}
+
+impl<C: MacCipher> BlockSizeUser for PmacCore<C> {
+    type BlockSize = C::BlockSize;
+}
+
+impl<C: MacCipher> OutputSizeUser for PmacCore<C> {
+    type OutputSize = C::BlockSize;
+}
+
+impl<C: MacCipher> InnerUser for PmacCore<C> {
+    type Inner = C;
+}
+
+impl<C: MacCipher> InnerInit for PmacCore<C> {
+    #[inline]
+    fn inner_init(cipher: C) -> Self {
+        let mut l = Block::<C>::default();
+        cipher.encrypt_block(&mut l);
+
+        let mut l_inv = l.clone();
+        inv_dbl::<C>(&mut l_inv);
+
+        let mut l_cache: [Block<C>; LC_SIZE] = Default::default();
+        l_cache[0] = l;
+        for i in 1..LC_SIZE {
+            let mut t = l_cache[i - 1].clone();
+            dbl::<C>(&mut t);
+            l_cache[i] = t;
+        }
+
+        let state = PmacState {
+            l_cache,
+            l_inv,
+            offset: Default::default(),
+            sigma: Default::default(),
+            counter: 0,
+        };
+        Self { cipher, state }
+    }
+}
+
+impl<C: MacCipher> BufferKindUser for PmacCore<C> {
+    // the last block has to be processed by `finalize_fixed_core`
+    type BufferKind = Lazy;
+}
+
+impl<C: MacCipher> UpdateCore for PmacCore<C> {
+    #[inline]
+    fn update_blocks(&mut self, blocks: &[Block<Self>]) {
+        let state = &mut self.state;
+        self.cipher
+            .encrypt_with_backend(PmacClosure { state, blocks });
+    }
+}
+
+impl<C: MacCipher> FixedOutputCore for PmacCore<C> {
+    #[inline]
+    fn finalize_fixed_core(&mut self, buffer: &mut Buffer<Self>, out: &mut Output<Self>) {
+        let pos = buffer.get_pos();
+        let mut last_block = buffer.pad_with_zeros();
+        let state = &mut self.state;
+        if pos == last_block.len() {
+            xor(&mut state.sigma, &state.l_inv);
+        } else {
+            last_block[pos] = 0x80;
+        }
+        xor(&mut state.sigma, &last_block);
+        self.cipher.encrypt_block(&mut state.sigma);
+        out.copy_from_slice(&state.sigma);
+    }
+}
+
+impl<C: MacCipher> MacMarker for PmacCore<C> {}
+
+impl<C: MacCipher> Reset for PmacCore<C> {
+    #[inline]
+    fn reset(&mut self) {
+        self.state.offset = Default::default();
+        self.state.sigma = Default::default();
+        self.state.counter = 0;
+    }
+}
+
+impl<C: MacCipher + AlgorithmName> AlgorithmName for PmacCore<C> {
+    fn write_alg_name(f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Pmac<")?;
+        C::write_alg_name(f)?;
+        f.write_str(">")
+    }
+}
+
+impl<C: MacCipher> fmt::Debug for PmacCore<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("PmacCore { ... }")
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<C: MacCipher> Drop for PmacState<C> {
+    fn drop(&mut self) {
+        self.l_cache.iter_mut().for_each(Zeroize::zeroize);
+        self.l_inv.zeroize();
+        self.offset.zeroize();
+        self.sigma.zeroize();
+    }
+}
+
+/// Closure which processes message blocks using the cipher backend.
+struct PmacClosure<'a, C: MacCipher> {
+    state: &'a mut PmacState<C>,
+    blocks: &'a [Block<C>],
+}
+
+impl<C: MacCipher> BlockSizeUser for PmacClosure<'_, C> {
+    type BlockSize = C::BlockSize;
+}
+
+impl<C: MacCipher> BlockCipherEncClosure for PmacClosure<'_, C> {
+    #[inline(always)]
+    fn call<B: BlockCipherEncBackend<BlockSize = Self::BlockSize>>(self, backend: &B) {
+        let Self { state, blocks } = self;
+
+        let mut chunks = blocks.chunks_exact(B::ParBlocksSize::USIZE);
+        for chunk in &mut chunks {
+            let mut par_blocks = ParBlocks::<B>::default();
+            for (dst, src) in par_blocks.iter_mut().zip(chunk) {
+                *dst = state.next_input(src);
+            }
+            backend.encrypt_par_blocks_inplace(&mut par_blocks);
+            for block in par_blocks.iter() {
+                xor(&mut state.sigma, block);
+            }
+        }
+
+        for block in chunks.remainder() {
+            let mut block = state.next_input(block);
+            backend.encrypt_block_inplace(&mut block);
+            xor(&mut state.sigma, &block);
+        }
+    }
+}
@@ -5,16 +5,22 @@
 
 use inout::{InOutBuf, NotEqualError};
 
+mod async_wrapper;
 mod core_api;
 mod errors;
+#[cfg(feature = "alloc")]
+mod guard;
 #[cfg(feature = "stream-wrapper")]
 mod wrapper;
 
+pub use async_wrapper::AsyncStreamCipherWrapper;
 pub use core_api::{
     StreamCipherBackend, StreamCipherClosure, StreamCipherCore, StreamCipherCounter,
     StreamCipherSeekCore,
 };
 pub use errors::{OverflowError, StreamCipherError};
+#[cfg(feature = "alloc")]
+pub use guard::KeystreamGuard;
 #[cfg(feature = "stream-wrapper")]
 pub use wrapper::StreamCipherCoreWrapper;
 
@@ -105,6 +111,25 @@ pub trait StreamCipher {
         Ok(())
     }
 
+    /// Apply keystream to data split across multiple buffers without checking
+    /// for keystream repetition.
+    ///
+    /// Keystream is applied continuously across buffer boundaries, i.e. the result
+    /// is equal to applying keystream to concatenation of `bufs`.
+    ///
+    /// <div><class = "warning">
+    /// <b>WARNING<b>
+    ///
+    /// This method should be used with extreme caution! Triggering keystream repetition can expose
+    /// the stream cipher to chosen plaintext attacks.
+    /// </div>
+    #[inline]
+    fn unchecked_apply_keystream_vectored(&mut self, bufs: &mut [&mut [u8]]) {
+        for buf in bufs {
+            self.unchecked_apply_keystream(buf);
+        }
+    }
+
     /// Apply keystream to `inout` data.
     ///
     /// # Errors
@@ -149,6 +174,25 @@ pub trait StreamCipher {
             .and_then(|buf| self.try_apply_keystream_inout(buf))
     }
 
+    /// Apply keystream to data split across multiple buffers.
+    ///
+    /// Keystream is applied continuously across buffer boundaries, i.e. the result
+    /// is equal to applying keystream to concatenation of `bufs`.
+    ///
+    /// # Errors
+    /// If the end of the keystream is reached with the given total length of buffers,
+    /// the method will return [`StreamCipherError`] without modifying `bufs`.
+    #[inline]
+    fn try_apply_keystream_vectored(
+        &mut self,
+        bufs: &mut [&mut [u8]],
+    ) -> Result<(), StreamCipherError> {
+        let len = bufs.iter().map(|buf| buf.len()).sum();
+        self.check_remaining(len)?;
+        self.unchecked_apply_keystream_vectored(bufs);
+        Ok(())
+    }
+
     /// Write keystream to `buf`.
     ///
     /// # Errors
@@ -187,6 +231,19 @@ pub trait StreamCipher {
             .expect("end of keystream reached");
     }
 
+    /// Apply keystream to data split across multiple buffers.
+    ///
+    /// Keystream is applied continuously across buffer boundaries, i.e. the result
+    /// is equal to applying keystream to concatenation of `bufs`.
+    ///
+    /// # Panics
+    /// If the end of the keystream is reached with the given total length of buffers.
+    #[inline]
+    fn apply_keystream_vectored(&mut self, bufs: &mut [&mut [u8]]) {
+        self.try_apply_keystream_vectored(bufs)
+            .expect("end of keystream reached");
+    }
+
     /// Apply keystream to data buffer-to-buffer.
     ///
     /// It will XOR generated keystream with data from the `input` buffer
@@ -265,6 +322,71 @@ impl<C: StreamCipher> StreamCipher for &mut C {
     fn unchecked_write_keystream(&mut self, buf: &mut [u8]) {
         C::unchecked_write_keystream(self, buf);
     }
+
+    #[inline]
+    fn unchecked_apply_keystream_vectored(&mut self, bufs: &mut [&mut [u8]]) {
+        C::unchecked_apply_keystream_vectored(self, bufs);
+    }
+}
+
+/// Asynchronous (self-synchronizing) stream cipher trait.
+///
+/// Unlike [`StreamCipher`], keystream generated by such ciphers depends on the previously
+/// processed ciphertext (e.g. in the CFB mode), so encryption and decryption are different
+/// operations. Data of arbitrary length can be processed, with partial blocks being carried
+/// over between method calls, i.e. processing a message in several parts produces the same
+/// result as processing it at once.
+///
+/// Implementation of this trait for block modes can be obtained using
+/// [`AsyncStreamCipherWrapper`].
+pub trait AsyncStreamCipher {
+    /// Encrypt data using `InOutBuf`.
+    fn encrypt_inout(&mut self, data: InOutBuf<'_, '_, u8>);
+
+    /// Decrypt data using `InOutBuf`.
+    fn decrypt_inout(&mut self, data: InOutBuf<'_, '_, u8>);
+
+    /// Encrypt data in place.
+    #[inline]
+    fn encrypt(&mut self, buf: &mut [u8]) {
+        self.encrypt_inout(buf.into());
+    }
+
+    /// Decrypt data in place.
+    #[inline]
+    fn decrypt(&mut self, buf: &mut [u8]) {
+        self.decrypt_inout(buf.into());
+    }
+
+    /// Encrypt data from buffer to buffer.
+    ///
+    /// # Errors
+    /// Returns [`NotEqualError`] if the `input` and `output` buffers have different lengths.
+    #[inline]
+    fn encrypt_b2b(&mut self, input: &[u8], output: &mut [u8]) -> Result<(), NotEqualError> {
+        InOutBuf::new(input, output).map(|buf| self.encrypt_inout(buf))
+    }
+
+    /// Decrypt data from buffer to buffer.
+    ///
+    /// # Errors
+    /// Returns [`NotEqualError`] if the `input` and `output` buffers have different lengths.
+    #[inline]
+    fn decrypt_b2b(&mut self, input: &[u8], output: &mut [u8]) -> Result<(), NotEqualError> {
+        InOutBuf::new(input, output).map(|buf| self.decrypt_inout(buf))
+    }
+}
+
+impl<C: AsyncStreamCipher> AsyncStreamCipher for &mut C {
+    #[inline]
+    fn encrypt_inout(&mut self, data: InOutBuf<'_, '_, u8>) {
+        C::encrypt_inout(self, data);
+    }
+
+    #[inline]
+    fn decrypt_inout(&mut self, data: InOutBuf<'_, '_, u8>) {
+        C::decrypt_inout(self, data);
+    }
 }
 
 /// Trait implemented for numeric types which can be used with the
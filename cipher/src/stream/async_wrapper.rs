@@ -0,0 +1,176 @@
+use super::AsyncStreamCipher;
+use crate::{BlockModeDecrypt, BlockModeEncrypt};
+use common::{
+    Block, BlockSizeUser, Iv, IvSizeUser, Key, KeyInit, KeyIvInit, KeySizeUser, typenum::Unsigned,
+};
+use core::fmt;
+use inout::InOutBuf;
+#[cfg(feature = "zeroize")]
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// Wrapper around a self-synchronizing block mode which implements [`AsyncStreamCipher`].
+///
+/// Whole blocks are processed directly by the wrapped mode using the [`BlockModeEncrypt`]
+/// and [`BlockModeDecrypt`] traits. When a partial block is started, its keystream
+/// is retrieved by encrypting a zero block and buffered until the block gets completed
+/// by the following method calls. The completed ciphertext block is then passed
+/// to the mode decryption to resynchronize the mode state.
+///
+/// It's assumed that the wrapped mode is self-synchronizing, i.e. that it computes output
+/// blocks by XOR-ing input blocks with keystream which depends only on the mode state,
+/// and that the state after decryption of a block depends only on the decrypted
+/// ciphertext block, e.g. as done by the full-block CFB mode. Using it with other modes
+/// (e.g. CBC or OFB) will result in incorrect output.
+///
+/// A partial block must be completed using the same operation which started it,
+/// i.e. interleaving encryption and decryption of a partial block results in garbage.
+pub struct AsyncStreamCipherWrapper<M: BlockSizeUser> {
+    mode: M,
+    /// Ciphertext bytes of the current partial block
+    buf: Block<M>,
+    /// Keystream of the current partial block
+    ks: Block<M>,
+    /// Number of processed bytes in the current partial block
+    pos: usize,
+}
+
+impl<M: BlockSizeUser> AsyncStreamCipherWrapper<M> {
+    /// Initialize from a block mode instance.
+    pub fn from_mode(mode: M) -> Self {
+        Self {
+            mode,
+            buf: Default::default(),
+            ks: Default::default(),
+            pos: 0,
+        }
+    }
+
+    /// Get reference to the wrapped block mode instance.
+    ///
+    /// Note that the mode state is not specified while a partial block is processed,
+    /// i.e. while [`partial_len`][Self::partial_len] is not equal to zero.
+    pub fn get_mode(&self) -> &M {
+        &self.mode
+    }
+
+    /// Get number of processed bytes in the current partial block.
+    pub fn partial_len(&self) -> usize {
+        self.pos
+    }
+}
+
+impl<M: BlockModeEncrypt + BlockModeDecrypt> AsyncStreamCipherWrapper<M> {
+    /// Process `data` using `blocks_fn` for whole blocks. The `encrypt` flag defines
+    /// whether ciphertext is written to the output or read from the input.
+    #[inline]
+    fn process_inout(
+        &mut self,
+        mut data: InOutBuf<'_, '_, u8>,
+        encrypt: bool,
+        blocks_fn: impl Fn(&mut M, InOutBuf<'_, '_, Block<M>>),
+    ) {
+        let bs = M::BlockSize::USIZE;
+        let pos = self.pos;
+        if pos != 0 {
+            let n = core::cmp::min(bs - pos, data.len());
+            let (head, tail) = data.split_at(n);
+            self.process_partial(head, encrypt);
+            if self.pos == bs {
+                self.mode.decrypt_block(&mut self.buf);
+                self.pos = 0;
+            }
+            data = tail;
+        }
+
+        let (blocks, tail) = data.into_chunks();
+        blocks_fn(&mut self.mode, blocks);
+
+        if !tail.is_empty() {
+            self.ks = Default::default();
+            self.mode.encrypt_block(&mut self.ks);
+            self.process_partial(tail, encrypt);
+        }
+    }
+
+    /// Apply the buffered keystream to `data` and save the processed ciphertext bytes.
+    #[inline]
+    fn process_partial(&mut self, mut data: InOutBuf<'_, '_, u8>, encrypt: bool) {
+        let buf = &mut self.buf[self.pos..][..data.len()];
+        if !encrypt {
+            buf.copy_from_slice(data.get_in());
+        }
+        data.xor_in2out(&self.ks[self.pos..][..data.len()]);
+        if encrypt {
+            buf.copy_from_slice(data.get_out());
+        }
+        self.pos += data.len();
+    }
+}
+
+impl<M> AsyncStreamCipher for AsyncStreamCipherWrapper<M>
+where
+    M: BlockModeEncrypt + BlockModeDecrypt,
+{
+    #[inline]
+    fn encrypt_inout(&mut self, data: InOutBuf<'_, '_, u8>) {
+        self.process_inout(data, true, M::encrypt_blocks_inout);
+    }
+
+    #[inline]
+    fn decrypt_inout(&mut self, data: InOutBuf<'_, '_, u8>) {
+        self.process_inout(data, false, M::decrypt_blocks_inout);
+    }
+}
+
+impl<M: BlockSizeUser + Clone> Clone for AsyncStreamCipherWrapper<M> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            mode: self.mode.clone(),
+            buf: self.buf.clone(),
+            ks: self.ks.clone(),
+            pos: self.pos,
+        }
+    }
+}
+
+impl<M: BlockSizeUser + fmt::Debug> fmt::Debug for AsyncStreamCipherWrapper<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AsyncStreamCipherWrapper")
+            .finish_non_exhaustive()
+    }
+}
+
+impl<M: KeySizeUser + BlockSizeUser> KeySizeUser for AsyncStreamCipherWrapper<M> {
+    type KeySize = M::KeySize;
+}
+
+impl<M: IvSizeUser + BlockSizeUser> IvSizeUser for AsyncStreamCipherWrapper<M> {
+    type IvSize = M::IvSize;
+}
+
+impl<M: KeyIvInit + BlockSizeUser> KeyIvInit for AsyncStreamCipherWrapper<M> {
+    #[inline]
+    fn new(key: &Key<Self>, iv: &Iv<Self>) -> Self {
+        Self::from_mode(M::new(key, iv))
+    }
+}
+
+impl<M: KeyInit + BlockSizeUser> KeyInit for AsyncStreamCipherWrapper<M> {
+    #[inline]
+    fn new(key: &Key<Self>) -> Self {
+        Self::from_mode(M::new(key))
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<M: BlockSizeUser> Drop for AsyncStreamCipherWrapper<M> {
+    fn drop(&mut self) {
+        self.buf.zeroize();
+        self.ks.zeroize();
+        self.pos.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<M: BlockSizeUser + ZeroizeOnDrop> ZeroizeOnDrop for AsyncStreamCipherWrapper<M> {}
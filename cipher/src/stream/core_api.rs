@@ -1,9 +1,11 @@
 use super::StreamCipherError;
+use crate::{BackendInfo, block::BackendInfoCtx};
 use crate::{
     array::{Array, ArraySize},
     typenum::Unsigned,
 };
 use common::{Block, BlockSizeUser, ParBlocks, ParBlocksSizeUser};
+use core::marker::PhantomData;
 use inout::{InOut, InOutBuf};
 
 /// Trait implemented by stream cipher backends.
@@ -28,6 +30,12 @@ pub trait StreamCipherBackend: ParBlocksSizeUser {
             self.gen_ks_block(block);
         }
     }
+
+    /// Get information about the backend.
+    #[inline(always)]
+    fn backend_info(&self) -> BackendInfo {
+        BackendInfo::unknown(Self::ParBlocksSize::USIZE)
+    }
 }
 
 /// Trait for [`StreamCipherBackend`] users.
@@ -145,6 +153,20 @@ pub trait StreamCipherCore: BlockSizeUser + Sized {
         self.try_apply_keystream_partial(buf)
             .expect("number of remaining blocks insufficient");
     }
+
+    /// Get information about the backend used for keystream generation.
+    ///
+    /// # Panics
+    /// If implementation of `process_with_backend` does not call the provided closure.
+    #[inline]
+    fn keystream_backend_info(&mut self) -> BackendInfo {
+        let mut info = None;
+        self.process_with_backend(BackendInfoCtx {
+            info: &mut info,
+            _pd: PhantomData,
+        });
+        info.expect("backend closure was not called")
+    }
 }
 
 // note: unfortunately, currently we can not write blanket impls of
@@ -290,3 +312,10 @@ impl<BS: ArraySize> StreamCipherClosure for ApplyBlocksCtx<'_, '_, BS> {
         }
     }
 }
+
+impl<BS: ArraySize> StreamCipherClosure for BackendInfoCtx<'_, BS> {
+    #[inline(always)]
+    fn call<B: StreamCipherBackend<BlockSize = BS>>(self, backend: &mut B) {
+        *self.info = Some(backend.backend_info());
+    }
+}
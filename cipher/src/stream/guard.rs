@@ -0,0 +1,179 @@
+use super::{OverflowError, StreamCipher, StreamCipherError, StreamCipherSeek};
+use crate::SeekNum;
+use alloc::vec::Vec;
+use common::{Iv, IvSizeUser, Key, KeyInit, KeyIvInit, KeySizeUser};
+use core::fmt;
+use inout::InOutBuf;
+
+/// Wrapper around a seekable stream cipher which detects keystream reuse.
+///
+/// The wrapper tracks ranges of keystream which were used for processing data
+/// and rejects attempts to use any part of them for a second time, e.g. after
+/// seeking back to an already used position. It's intended to be used in tests
+/// for catching accidental keystream reuse and has a memory cost proportional
+/// to the number of non-contiguous used ranges.
+///
+/// Checked methods (e.g. [`StreamCipher::try_apply_keystream`]) return
+/// [`StreamCipherError`] on reuse, while the other keystream application methods
+/// (including the "unchecked" ones) panic.
+pub struct KeystreamGuard<C> {
+    cipher: C,
+    /// Sorted non-overlapping and non-adjacent half-open ranges of used keystream
+    used: Vec<(u128, u128)>,
+}
+
+impl<C> KeystreamGuard<C> {
+    /// Wrap stream cipher instance.
+    pub fn from_cipher(cipher: C) -> Self {
+        Self {
+            cipher,
+            used: Vec::new(),
+        }
+    }
+
+    /// Get reference to the wrapped stream cipher instance.
+    pub fn get_cipher(&self) -> &C {
+        &self.cipher
+    }
+
+    /// Unwrap the stream cipher instance.
+    pub fn into_cipher(self) -> C {
+        self.cipher
+    }
+
+    /// Check whether keystream range `[start, end)` intersects with used ranges.
+    fn is_used(&self, start: u128, end: u128) -> bool {
+        if start == end {
+            return false;
+        }
+        let i = self.used.partition_point(|&(_, e)| e <= start);
+        self.used.get(i).is_some_and(|&(s, _)| s < end)
+    }
+
+    /// Mark keystream range `[start, end)` as used.
+    fn mark_used(&mut self, start: u128, end: u128) {
+        let i = self.used.partition_point(|&(_, e)| e < start);
+        let j = self.used.partition_point(|&(s, _)| s <= end);
+        if i == j {
+            self.used.insert(i, (start, end));
+        } else {
+            let start = start.min(self.used[i].0);
+            let end = end.max(self.used[j - 1].1);
+            self.used.drain(i + 1..j);
+            self.used[i] = (start, end);
+        }
+    }
+}
+
+impl<C: StreamCipherSeek> KeystreamGuard<C> {
+    /// Get keystream range which will be used for processing `len` bytes.
+    fn next_range(&self, len: usize) -> Result<(u128, u128), StreamCipherError> {
+        let start: u128 = self
+            .cipher
+            .try_current_pos()
+            .map_err(|_| StreamCipherError)?;
+        let len = u128::try_from(len).map_err(|_| StreamCipherError)?;
+        let end = start.checked_add(len).ok_or(StreamCipherError)?;
+        Ok((start, end))
+    }
+
+    /// Check that processing of `len` bytes does not reuse keystream.
+    fn check_reuse(&self, len: usize) -> Result<(), StreamCipherError> {
+        let (start, end) = self.next_range(len)?;
+        if self.is_used(start, end) {
+            Err(StreamCipherError)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Mark keystream used for processing `len` bytes.
+    ///
+    /// # Panics
+    /// If the keystream was already used.
+    fn use_keystream(&mut self, len: usize) {
+        let (start, end) = self
+            .next_range(len)
+            .expect("keystream position cannot be represented");
+        assert!(!self.is_used(start, end), "keystream reuse detected");
+        if start != end {
+            self.mark_used(start, end);
+        }
+    }
+}
+
+impl<C: StreamCipher + StreamCipherSeek> StreamCipher for KeystreamGuard<C> {
+    #[inline]
+    fn check_remaining(&self, data_len: usize) -> Result<(), StreamCipherError> {
+        self.cipher.check_remaining(data_len)?;
+        self.check_reuse(data_len)
+    }
+
+    #[inline]
+    fn unchecked_apply_keystream_inout(&mut self, buf: InOutBuf<'_, '_, u8>) {
+        self.use_keystream(buf.len());
+        self.cipher.unchecked_apply_keystream_inout(buf);
+    }
+
+    #[inline]
+    fn unchecked_write_keystream(&mut self, buf: &mut [u8]) {
+        self.use_keystream(buf.len());
+        self.cipher.unchecked_write_keystream(buf);
+    }
+
+    #[inline]
+    fn unchecked_apply_keystream_vectored(&mut self, bufs: &mut [&mut [u8]]) {
+        self.use_keystream(bufs.iter().map(|buf| buf.len()).sum());
+        self.cipher.unchecked_apply_keystream_vectored(bufs);
+    }
+}
+
+impl<C: StreamCipherSeek> StreamCipherSeek for KeystreamGuard<C> {
+    #[inline]
+    fn try_current_pos<T: SeekNum>(&self) -> Result<T, OverflowError> {
+        self.cipher.try_current_pos()
+    }
+
+    #[inline]
+    fn try_seek<T: SeekNum>(&mut self, pos: T) -> Result<(), StreamCipherError> {
+        self.cipher.try_seek(pos)
+    }
+}
+
+impl<C: Clone> Clone for KeystreamGuard<C> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            cipher: self.cipher.clone(),
+            used: self.used.clone(),
+        }
+    }
+}
+
+impl<C: fmt::Debug> fmt::Debug for KeystreamGuard<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeystreamGuard").finish_non_exhaustive()
+    }
+}
+
+impl<C: KeySizeUser> KeySizeUser for KeystreamGuard<C> {
+    type KeySize = C::KeySize;
+}
+
+impl<C: IvSizeUser> IvSizeUser for KeystreamGuard<C> {
+    type IvSize = C::IvSize;
+}
+
+impl<C: KeyIvInit> KeyIvInit for KeystreamGuard<C> {
+    #[inline]
+    fn new(key: &Key<Self>, iv: &Iv<Self>) -> Self {
+        Self::from_cipher(C::new(key, iv))
+    }
+}
+
+impl<C: KeyInit> KeyInit for KeystreamGuard<C> {
+    #[inline]
+    fn new(key: &Key<Self>) -> Self {
+        Self::from_cipher(C::new(key))
+    }
+}
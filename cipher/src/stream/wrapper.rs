@@ -1,4 +1,4 @@
-use crate::StreamCipherCounter;
+use crate::{BackendInfo, StreamCipherCounter};
 
 use super::{
     OverflowError, SeekNum, StreamCipher, StreamCipherCore, StreamCipherSeek, StreamCipherSeekCore,
@@ -6,9 +6,12 @@ use super::{
 };
 use block_buffer::{BlockSizes, ReadBuffer};
 use common::{
-    Iv, IvSizeUser, Key, KeyInit, KeyIvInit, KeySizeUser, array::Array, typenum::Unsigned,
+    Block, Iv, IvSizeUser, Key, KeyInit, KeyIvInit, KeySizeUser,
+    array::{Array, ArraySize},
+    hazmat::{DeserializeStateError, SerializableState, SerializedState},
+    typenum::{Sum, Unsigned},
 };
-use core::fmt;
+use core::{fmt, ops::Add};
 use inout::InOutBuf;
 #[cfg(feature = "zeroize")]
 use zeroize::ZeroizeOnDrop;
@@ -67,6 +70,14 @@ where
     pub fn get_core(&self) -> &T {
         &self.core
     }
+
+    /// Get information about the backend used by the wrapped [`StreamCipherCore`] instance.
+    ///
+    /// # Panics
+    /// If implementation of `process_with_backend` does not call the provided closure.
+    pub fn keystream_backend_info(&mut self) -> BackendInfo {
+        self.core.keystream_backend_info()
+    }
 }
 
 impl<T> StreamCipher for StreamCipherCoreWrapper<T>
@@ -125,6 +136,101 @@ where
             |tail_ks| tail.copy_from_slice(tail_ks),
         );
     }
+
+    #[inline]
+    fn unchecked_apply_keystream_vectored(&mut self, bufs: &mut [&mut [u8]]) {
+        let bs = T::BlockSize::USIZE;
+        let mut bufs = Segments::new(bufs);
+
+        let head_ks = self.buffer.read_cached(bufs.remaining);
+        bufs.xor(head_ks);
+
+        let mut ks: [Block<T>; VECTORED_KS_BLOCKS] = Default::default();
+        let mut n_blocks = bufs.remaining / bs;
+        while n_blocks != 0 {
+            // process whole blocks inside the current segment in-place
+            let (blocks, _) = Array::slice_as_chunks_mut(bufs.current());
+            let n = blocks.len().min(n_blocks);
+            if n != 0 {
+                self.core.apply_keystream_blocks(&mut blocks[..n]);
+                bufs.advance(n * bs);
+                n_blocks -= n;
+                continue;
+            }
+
+            // generate keystream for blocks which cross segment boundaries
+            let ks = &mut ks[..n_blocks.min(VECTORED_KS_BLOCKS)];
+            self.core.write_keystream_blocks(ks);
+            bufs.xor(Array::slice_as_flattened(ks));
+            n_blocks -= ks.len();
+        }
+
+        self.buffer.write_block(
+            bufs.remaining,
+            |b| self.core.write_keystream_block(b),
+            |tail_ks| bufs.xor(tail_ks),
+        );
+    }
+}
+
+/// Maximum number of keystream blocks generated at once for blocks which cross
+/// segment boundaries during vectored keystream application.
+const VECTORED_KS_BLOCKS: usize = 8;
+
+/// Cursor over segments of data processed by vectored keystream application.
+struct Segments<'a, 'b> {
+    bufs: &'a mut [&'b mut [u8]],
+    /// Position inside the first segment
+    pos: usize,
+    /// Total number of remaining bytes
+    remaining: usize,
+}
+
+impl<'a, 'b> Segments<'a, 'b> {
+    fn new(bufs: &'a mut [&'b mut [u8]]) -> Self {
+        let remaining = bufs.iter().map(|buf| buf.len()).sum();
+        Self {
+            bufs,
+            pos: 0,
+            remaining,
+        }
+    }
+
+    /// Get remaining data in the current non-empty segment.
+    fn current(&mut self) -> &mut [u8] {
+        while let Some(buf) = self.bufs.first() {
+            if self.pos < buf.len() {
+                break;
+            }
+            self.bufs = &mut core::mem::take(&mut self.bufs)[1..];
+            self.pos = 0;
+        }
+        match self.bufs.first_mut() {
+            Some(buf) => &mut buf[self.pos..],
+            None => &mut [],
+        }
+    }
+
+    fn advance(&mut self, n: usize) {
+        self.pos += n;
+        self.remaining -= n;
+    }
+
+    /// XOR `ks` with the segment data starting from the current position.
+    fn xor(&mut self, mut ks: &[u8]) {
+        debug_assert!(ks.len() <= self.remaining);
+        while !ks.is_empty() {
+            let buf = self.current();
+            let n = buf.len().min(ks.len());
+            if n == 0 {
+                break;
+            }
+            let (left, right) = ks.split_at(n);
+            buf.iter_mut().zip(left).for_each(|(a, b)| *a ^= b);
+            ks = right;
+            self.advance(n);
+        }
+    }
 }
 
 impl<T> StreamCipherSeek for StreamCipherCoreWrapper<T>
@@ -210,6 +316,40 @@ where
     }
 }
 
+impl<T> SerializableState for StreamCipherCoreWrapper<T>
+where
+    T: StreamCipherCore + SerializableState,
+    T::BlockSize: BlockSizes,
+    T::SerializedStateSize: Add<T::BlockSize>,
+    Sum<T::SerializedStateSize, T::BlockSize>: ArraySize,
+{
+    type SerializedStateSize = Sum<T::SerializedStateSize, T::BlockSize>;
+
+    #[inline]
+    fn serialize(&self) -> SerializedState<Self> {
+        let serialized_core = self.core.serialize();
+        let serialized_buf = self.buffer.serialize();
+        serialized_core.concat(serialized_buf)
+    }
+
+    #[inline]
+    fn deserialize(
+        serialized_state: &SerializedState<Self>,
+    ) -> Result<Self, DeserializeStateError> {
+        let (serialized_core, serialized_buf) =
+            serialized_state.split_at(T::SerializedStateSize::USIZE);
+        let serialized_core =
+            <&SerializedState<T>>::try_from(serialized_core).map_err(|_| DeserializeStateError)?;
+        let serialized_buf = <&Array<u8, T::BlockSize>>::try_from(serialized_buf)
+            .map_err(|_| DeserializeStateError)?;
+
+        let core = T::deserialize(serialized_core)?;
+        let buffer = ReadBuffer::deserialize(serialized_buf).map_err(|_| DeserializeStateError)?;
+
+        Ok(Self { core, buffer })
+    }
+}
+
 #[cfg(feature = "zeroize")]
 impl<T> ZeroizeOnDrop for StreamCipherCoreWrapper<T>
 where
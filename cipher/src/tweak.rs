@@ -1,6 +1,7 @@
 //! Traits used to define functionality of [tweakable block ciphers][1].
 //!
 //! [1]: https://people.eecs.berkeley.edu/~daw/papers/tweak-crypto02.pdf
+use crate::BackendInfo;
 use common::{
     Block, BlockSizeUser,
     array::{Array, ArraySize},
@@ -126,6 +127,12 @@ pub trait TweakBlockCipherEncBackend: BlockSizeUser + TweakSizeUser {
     ) {
         self.encrypt_block_inout(tweak, (in_block, out_block).into());
     }
+
+    /// Get information about the backend.
+    #[inline(always)]
+    fn backend_info(&self) -> BackendInfo {
+        BackendInfo::unknown(1)
+    }
 }
 
 /// Trait implemented by block cipher mode decryption backends.
@@ -149,4 +156,10 @@ pub trait TweakBlockCipherDecBackend: BlockSizeUser + TweakSizeUser {
     ) {
         self.decrypt_block_inout(tweak, (in_block, out_block).into());
     }
+
+    /// Get information about the backend.
+    #[inline(always)]
+    fn backend_info(&self) -> BackendInfo {
+        BackendInfo::unknown(1)
+    }
 }
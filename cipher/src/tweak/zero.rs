@@ -7,8 +7,8 @@ use super::{
     TweakBlockCipherEncBackend, TweakBlockCipherEncrypt, TweakSizeUser,
 };
 use crate::{
-    BlockCipherDecBackend, BlockCipherDecClosure, BlockCipherDecrypt, BlockCipherEncBackend,
-    BlockCipherEncClosure, BlockCipherEncrypt, BlockSizeUser, consts::U1,
+    BackendInfo, BlockCipherDecBackend, BlockCipherDecClosure, BlockCipherDecrypt,
+    BlockCipherEncBackend, BlockCipherEncClosure, BlockCipherEncrypt, BlockSizeUser, consts::U1,
     tweak::TweakBlockCipherEncClosure,
 };
 
@@ -105,6 +105,14 @@ where
     fn encrypt_block(&self, block: inout::InOut<'_, '_, Block<Self>>) {
         self.backend.encrypt_block_inout(&Default::default(), block);
     }
+
+    #[inline]
+    fn backend_info(&self) -> BackendInfo {
+        BackendInfo {
+            par_blocks: 1,
+            ..self.backend.backend_info()
+        }
+    }
 }
 
 impl<BS: ArraySize, B> BlockCipherDecBackend for BackendWrapper<'_, BS, B>
@@ -115,4 +123,12 @@ where
     fn decrypt_block(&self, block: inout::InOut<'_, '_, Block<Self>>) {
         self.backend.decrypt_block_inout(&Default::default(), block);
     }
+
+    #[inline]
+    fn backend_info(&self) -> BackendInfo {
+        BackendInfo {
+            par_blocks: 1,
+            ..self.backend.backend_info()
+        }
+    }
 }
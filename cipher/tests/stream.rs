@@ -3,6 +3,7 @@
 use cipher::{
     BlockSizeUser, IvSizeUser, KeyIvInit, KeySizeUser, ParBlocksSizeUser, StreamCipherBackend,
     StreamCipherClosure, StreamCipherCore, StreamCipherSeekCore,
+    common::hazmat::{DeserializeStateError, SerializableState, SerializedState},
     consts::{U1, U4, U16},
 };
 use hex_literal::hex;
@@ -85,6 +86,27 @@ impl StreamCipherSeekCore for DummyStreamCipherCore {
     }
 }
 
+impl SerializableState for DummyStreamCipherCore {
+    type SerializedStateSize = U16;
+
+    fn serialize(&self) -> SerializedState<Self> {
+        let mut res = SerializedState::<Self>::default();
+        res[..8].copy_from_slice(&self.key_iv.to_le_bytes());
+        res[8..].copy_from_slice(&self.pos.to_le_bytes());
+        res
+    }
+
+    fn deserialize(
+        serialized_state: &SerializedState<Self>,
+    ) -> Result<Self, DeserializeStateError> {
+        let (key_iv, pos) = serialized_state.split_at(8);
+        Ok(Self {
+            key_iv: u64::from_le_bytes(key_iv.try_into().map_err(|_| DeserializeStateError)?),
+            pos: u64::from_le_bytes(pos.try_into().map_err(|_| DeserializeStateError)?),
+        })
+    }
+}
+
 #[test]
 fn dummy_stream_cipher_core() {
     let mut cipher = DummyStreamCipherCore::new(&KEY.into(), &IV.into());
@@ -103,6 +125,17 @@ fn dummy_stream_cipher_core() {
     assert_eq!(cipher.get_block_pos(), 201);
 }
 
+#[test]
+fn dummy_stream_cipher_backend_info() {
+    let mut cipher = DummyStreamCipherCore::new(&KEY.into(), &IV.into());
+    let info = cipher.keystream_backend_info();
+    assert_eq!(info.name, "unknown");
+    assert_eq!(info.par_blocks, 1);
+    assert!(!info.constant_time);
+    // retrieving backend information does not affect the keystream position
+    assert_eq!(cipher.get_block_pos(), 0);
+}
+
 #[cfg(feature = "stream-wrapper")]
 mod wrapper {
     use core::panic;
@@ -166,6 +199,138 @@ mod wrapper {
         }
     }
 
+    #[test]
+    fn dummy_stream_cipher_serialization() {
+        let mut buf = [0u8; 64];
+        let mut expected = [0u8; 64];
+        DummyStreamCipher::new(&KEY.into(), &IV.into()).apply_keystream(&mut expected);
+
+        for pos in 0..buf.len() {
+            let mut cipher = DummyStreamCipher::new(&KEY.into(), &IV.into());
+            buf.fill(0);
+            cipher.apply_keystream(&mut buf[..pos]);
+
+            let state = cipher.serialize();
+            let mut cipher = DummyStreamCipher::deserialize(&state).unwrap();
+            assert_eq!(cipher.current_pos::<usize>(), pos);
+            cipher.apply_keystream(&mut buf[pos..]);
+            assert_eq!(buf, expected);
+        }
+
+        let mut state = DummyStreamCipher::new(&KEY.into(), &IV.into()).serialize();
+        // invalid buffer position
+        state[16] = 0;
+        assert!(DummyStreamCipher::deserialize(&state).is_err());
+        state[16] = 17;
+        assert!(DummyStreamCipher::deserialize(&state).is_err());
+    }
+
+    #[test]
+    fn dummy_stream_cipher_backend_info() {
+        let mut cipher = DummyStreamCipher::new(&KEY.into(), &IV.into());
+        let info = cipher.keystream_backend_info();
+        assert_eq!(info.name, "unknown");
+        assert_eq!(cipher.current_pos::<usize>(), 0);
+    }
+
+    #[test]
+    fn dummy_stream_cipher_vectored() {
+        let mut expected = [0u8; 300];
+        DummyStreamCipher::new(&KEY.into(), &IV.into()).apply_keystream(&mut expected);
+
+        let segment_lens: &[&[usize]] = &[
+            &[],
+            &[300],
+            &[0, 5, 0, 16, 11, 0],
+            &[1; 40],
+            &[7, 9, 3, 100, 15, 17, 33],
+            &[16, 32, 48, 64],
+            &[130, 1, 2, 3],
+        ];
+        for &lens in segment_lens {
+            for start in [0, 3, 16] {
+                let mut cipher = DummyStreamCipher::new(&KEY.into(), &IV.into());
+                let mut buf = [0u8; 300];
+                cipher.apply_keystream(&mut buf[..start]);
+
+                let mut rest = &mut buf[start..];
+                let mut bufs = Vec::new();
+                for &len in lens {
+                    let len = len.min(rest.len());
+                    let (segment, tail) = rest.split_at_mut(len);
+                    bufs.push(segment);
+                    rest = tail;
+                }
+                let len = start + bufs.iter().map(|b| b.len()).sum::<usize>();
+                cipher.apply_keystream_vectored(&mut bufs);
+                assert_eq!(cipher.current_pos::<usize>(), len);
+
+                // the keystream must stay continuous after vectored processing
+                cipher.apply_keystream(&mut buf[len..]);
+                assert_eq!(buf, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn dummy_stream_cipher_vectored_limit() {
+        let mut cipher = DummyStreamCipher::new(&KEY.into(), &IV.into());
+        let keystream_end = 1u128 << 68;
+        cipher.seek(keystream_end - 64);
+
+        let mut buf1 = [0u8; 20];
+        let mut buf2 = [0u8; 30];
+        let res = cipher.try_apply_keystream_vectored(&mut [&mut buf1, &mut buf2]);
+        assert!(res.is_err());
+        assert_eq!(buf1, [0; 20]);
+        assert_eq!(cipher.current_pos::<u128>(), keystream_end - 64);
+
+        let res = cipher.try_apply_keystream_vectored(&mut [&mut buf1, &mut buf2[..10]]);
+        assert!(res.is_ok());
+        assert_eq!(cipher.current_pos::<u128>(), keystream_end - 34);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn keystream_guard() {
+        use cipher::KeystreamGuard;
+
+        let mut expected = [0u8; 64];
+        DummyStreamCipher::new(&KEY.into(), &IV.into()).apply_keystream(&mut expected);
+
+        let mut cipher = KeystreamGuard::<DummyStreamCipher>::new(&KEY.into(), &IV.into());
+        let mut buf = [0u8; 64];
+        cipher.apply_keystream(&mut buf[..10]);
+        cipher.seek(30);
+        let (a, b) = buf[30..40].split_at_mut(5);
+        cipher.apply_keystream_vectored(&mut [a, b]);
+        cipher.seek(10);
+        cipher.apply_keystream(&mut buf[10..20]);
+
+        // ranges overlapping with used keystream are rejected
+        for (start, end) in [(0, 1), (5, 25), (19, 31), (25, 45), (39, 40), (0, 64)] {
+            cipher.seek(start);
+            let res = cipher.try_apply_keystream(&mut buf[start..end]);
+            assert!(res.is_err());
+            assert_eq!(cipher.current_pos::<usize>(), start);
+        }
+        cipher.seek(15);
+        assert!(cipher.try_apply_keystream(&mut []).is_ok());
+
+        // unused gaps can still be filled
+        cipher.seek(20);
+        cipher.apply_keystream(&mut buf[20..30]);
+        cipher.seek(40);
+        cipher.try_apply_keystream(&mut buf[40..]).unwrap();
+        assert_eq!(buf, expected);
+
+        let res = std::panic::catch_unwind(move || {
+            cipher.seek(63);
+            cipher.apply_keystream(&mut [0u8; 1]);
+        });
+        assert!(res.is_err());
+    }
+
     #[cfg(feature = "dev")]
     cipher::stream_cipher_test!(
         dummy_stream_cipher,
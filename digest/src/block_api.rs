@@ -3,13 +3,15 @@
 //! Usage of traits in this module in user code is discouraged. Instead use
 //! core algorithm wrapped by the wrapper types, which implement the
 //! higher-level traits.
-use crate::{Digest, HashMarker, InvalidOutputSize};
+use crate::{Digest, HashMarker, InvalidOutputSize, XofSeekError};
 
 pub use block_buffer::{Eager, Lazy};
-pub use common::{AlgorithmName, Block, BlockSizeUser, OutputSizeUser, Reset};
+pub use common::{
+    AlgorithmName, Block, BlockSizeUser, OutputSizeUser, ParBlocks, ParBlocksSizeUser, Reset,
+};
 
 use block_buffer::{BlockBuffer, BlockSizes, BufferKind};
-use common::Output;
+use common::{Output, array::Array};
 
 mod ct_variable;
 pub use ct_variable::CtOutWrapper;
@@ -24,6 +26,22 @@ pub trait UpdateCore: BlockSizeUser {
     fn update_blocks(&mut self, blocks: &[Block<Self>]);
 }
 
+/// Types which can process blocks of several independent messages in parallel.
+///
+/// Every lane is represented by a separate core instance. Number of lanes is
+/// defined by the [`ParBlocksSizeUser::ParBlocksSize`] associated type.
+pub trait ParUpdateCore: UpdateCore + ParBlocksSizeUser + Sized {
+    /// Update state of every lane using the corresponding block.
+    ///
+    /// The default implementation processes lanes sequentially. Implementations
+    /// with multi-buffer backends should override it.
+    fn update_par_blocks(lanes: &mut Array<Self, Self::ParBlocksSize>, blocks: &ParBlocks<Self>) {
+        for (lane, block) in lanes.iter_mut().zip(blocks.iter()) {
+            lane.update_blocks(core::slice::from_ref(block));
+        }
+    }
+}
+
 /// Sub-trait of [`BlockSizeUser`] implemented if `BlockSize` is
 /// bigger than `U0` and smaller than `U256`.
 ///
@@ -100,6 +118,36 @@ pub trait XofReaderCore: BlockSizeUser {
     fn read_block(&mut self) -> Block<Self>;
 }
 
+/// Core reader trait for XOF results with random access to output blocks.
+///
+/// Counter-mode XOFs (e.g. BLAKE3) which can compute an arbitrary output block
+/// directly should override [`seek_block`][XofReaderCoreSeek::seek_block].
+pub trait XofReaderCoreSeek: XofReaderCore {
+    /// Get index of the block which will be returned by the next
+    /// [`read_block`][XofReaderCore::read_block] call.
+    fn block_pos(&self) -> u64;
+
+    /// Set index of the block which will be returned by the next
+    /// [`read_block`][XofReaderCore::read_block] call.
+    ///
+    /// The default implementation skips forward by generating and discarding
+    /// output blocks.
+    ///
+    /// # Errors
+    /// The default implementation returns error if `pos` is smaller
+    /// than the current block position.
+    fn seek_block(&mut self, pos: u64) -> Result<(), XofSeekError> {
+        let cur = self.block_pos();
+        if pos < cur {
+            return Err(XofSeekError);
+        }
+        for _ in cur..pos {
+            self.read_block();
+        }
+        Ok(())
+    }
+}
+
 /// Core trait for hash functions with variable output size.
 ///
 /// Maximum output size is equal to [`OutputSizeUser::OutputSize`].
@@ -458,6 +458,26 @@ macro_rules! buffer_fixed {
             }
         }
 
+        impl$(< $( $lt $( : $clt $(+ $dlt )* )? ),+ >)? $crate::StateVersion for $name$(< $( $lt ),+ >)? {
+            const STATE_VERSION: &'static str = concat!(env!("CARGO_PKG_NAME"), " ", env!("CARGO_PKG_VERSION"));
+        }
+
+        #[cfg(feature = "serde")]
+        impl$(< $( $lt $( : $clt $(+ $dlt )* )? ),+ >)? $crate::serde::Serialize for $name$(< $( $lt ),+ >)? {
+            #[inline]
+            fn serialize<S: $crate::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                $crate::serde_state::serialize(self, serializer)
+            }
+        }
+
+        #[cfg(feature = "serde")]
+        impl<'de$(, $( $lt $( : $clt $(+ $dlt )* )? ),+ )?> $crate::serde::Deserialize<'de> for $name$(< $( $lt ),+ >)? {
+            #[inline]
+            fn deserialize<D: $crate::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                $crate::serde_state::deserialize(deserializer)
+            }
+        }
+
         $crate::buffer_fixed!(impl_inner: $name$(< $( $lt $( : $clt $(+ $dlt )* )? ),+ >)?($core_ty); $($trait_name)*;);
     };
 
@@ -276,6 +276,51 @@ macro_rules! buffer_xof {
         $crate::buffer_xof!(impl_inner: $name($core_ty); $($trait_name)*;);
     };
 
+    // Implements `XofReaderSeek`
+    (
+        impl_inner: $name:ident($core_ty:ty);
+        XofReaderSeek $($trait_name:ident)*;
+    ) => {
+        impl $crate::XofReaderSeek for $name {
+            #[inline]
+            fn current_pos(&self) -> u64 {
+                use $crate::typenum::Unsigned;
+
+                let block_size = <$core_ty as $crate::block_api::BlockSizeUser>::BlockSize::U64;
+                let block_pos = $crate::block_api::XofReaderCoreSeek::block_pos(&self.core);
+                let remaining = self.buffer.remaining() as u64;
+                block_pos * block_size - remaining
+            }
+
+            #[inline]
+            fn try_seek(&mut self, pos: u64) -> Result<(), $crate::XofSeekError> {
+                use $crate::typenum::Unsigned;
+
+                let block_size = <$core_ty as $crate::block_api::BlockSizeUser>::BlockSize::U64;
+                let cur_pos = $crate::XofReaderSeek::current_pos(self);
+                let Self { core, buffer } = self;
+                let buffered_end = cur_pos + buffer.remaining() as u64;
+                if (cur_pos..buffered_end).contains(&pos) {
+                    // Target position is inside the buffered block
+                    buffer.read_cached((pos - cur_pos) as usize);
+                    return Ok(());
+                }
+                $crate::block_api::XofReaderCoreSeek::seek_block(core, pos / block_size)?;
+                buffer.reset();
+                // Offset is smaller than block size, so the cast is lossless
+                let offset = (pos % block_size) as usize;
+                buffer.write_block(
+                    offset,
+                    |block| *block = $crate::block_api::XofReaderCore::read_block(core),
+                    |_| {},
+                );
+                Ok(())
+            }
+        }
+
+        $crate::buffer_xof!(impl_inner: $name($core_ty); $($trait_name)*;);
+    };
+
     // Implements `SerializableState`
     (
         impl_inner: $name:ident($core_ty:ty);
@@ -314,6 +359,26 @@ macro_rules! buffer_xof {
             }
         }
 
+        impl $crate::StateVersion for $name {
+            const STATE_VERSION: &'static str = concat!(env!("CARGO_PKG_NAME"), " ", env!("CARGO_PKG_VERSION"));
+        }
+
+        #[cfg(feature = "serde")]
+        impl $crate::serde::Serialize for $name {
+            #[inline]
+            fn serialize<S: $crate::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                $crate::serde_state::serialize(self, serializer)
+            }
+        }
+
+        #[cfg(feature = "serde")]
+        impl<'de> $crate::serde::Deserialize<'de> for $name {
+            #[inline]
+            fn deserialize<D: $crate::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                $crate::serde_state::deserialize(deserializer)
+            }
+        }
+
         $crate::buffer_xof!(impl_inner: $name($core_ty); $($trait_name)*;);
     };
 }
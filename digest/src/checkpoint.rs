@@ -0,0 +1,310 @@
+//! Algorithm-tagged and versioned checkpoints of hasher state.
+//!
+//! [`SerializableState`][common::hazmat::SerializableState] produces raw bytes
+//! without any information about the algorithm or the format of the state.
+//! Functions in this module wrap the serialized state into a self-describing
+//! container, which allows to reliably detect attempts to restore state into
+//! a wrong hasher type or to restore state produced by an incompatible implementation.
+//!
+//! # Format
+//!
+//! Checkpoint consists of the following fields (integers are encoded in
+//! little-endian order):
+//!
+//! | Field               | Size        |
+//! |---------------------|-------------|
+//! | Magic bytes `DGCP`  | 4           |
+//! | Checkpoint format   | 1           |
+//! | Version length `v`  | 1           |
+//! | State version       | `v`         |
+//! | Name length `n`     | 1           |
+//! | Algorithm name      | `n`         |
+//! | OID length `k`      | 1           |
+//! | Algorithm OID       | `k`         |
+//! | Stream position     | 8           |
+//! | State length `m`    | 4           |
+//! | Serialized state    | `m`         |
+//! | CRC-32 checksum     | 4           |
+//!
+//! The state version is equal to [`StateVersion::STATE_VERSION`] of the hasher type.
+//! For hashers generated by the buffer macros of this crate it contains name and version
+//! of the hash implementation crate, so checkpoints created by a different release
+//! of the implementation are rejected without any additional actions from users.
+//!
+//! The OID is stored in the BER encoding without tag and length, and is empty
+//! for checkpoints created by [`save`]. The stream position is provided by user and
+//! is intended for storing number of input bytes already absorbed by the hasher.
+//! The state length is equal to the serialized state size of the hasher type
+//! and is checked during restoration separately from the algorithm name.
+//!
+//! The checksum is computed over all preceding bytes and protects against
+//! accidental corruption only, it does not provide any protection against
+//! intentional modifications.
+
+use crate::StateVersion;
+use alloc::{string::ToString, vec::Vec};
+use common::{AlgorithmName, hazmat::SerializedState, typenum::Unsigned};
+#[cfg(feature = "oid")]
+use const_oid::AssociatedOid;
+use core::{fmt, marker::PhantomData};
+
+/// Magic bytes at the start of every checkpoint.
+const MAGIC: [u8; 4] = *b"DGCP";
+/// Version of the checkpoint container format.
+const FORMAT_VERSION: u8 = 1;
+/// Total length of the fixed-size header fields: magic bytes, checkpoint format,
+/// lengths of the state version, algorithm name and OID, stream position, and state length.
+const HEADER_LEN: usize = MAGIC.len() + 1 + 1 + 1 + 1 + 8 + 4;
+/// Length of the checksum.
+const CHECKSUM_LEN: usize = 4;
+
+/// Save state of `hasher` into a checkpoint.
+///
+/// `position` is the position in the input stream (i.e. number of bytes already
+/// absorbed by `hasher`) which is stored alongside the state and returned by [`restore`].
+///
+/// # Panics
+/// If the state version or the algorithm name is longer than 255 bytes,
+/// or the serialized state is longer than `u32::MAX` bytes.
+#[must_use]
+pub fn save<D>(hasher: &D, position: u64) -> Vec<u8>
+where
+    D: StateVersion + AlgorithmName,
+{
+    save_inner(hasher, &[], position)
+}
+
+/// Save state of `hasher` into a checkpoint which also records the algorithm OID.
+///
+/// See [`save`] for description of the arguments. Checkpoints created by this function
+/// can be restored using both [`restore`] and [`restore_with_oid`].
+///
+/// # Panics
+/// If the state version or the algorithm name is longer than 255 bytes,
+/// or the serialized state is longer than `u32::MAX` bytes.
+#[cfg(feature = "oid")]
+#[must_use]
+pub fn save_with_oid<D>(hasher: &D, position: u64) -> Vec<u8>
+where
+    D: StateVersion + AlgorithmName + AssociatedOid,
+{
+    save_inner(hasher, D::OID.as_bytes(), position)
+}
+
+/// Restore hasher state from `checkpoint` created by [`save`] or `save_with_oid`.
+///
+/// Returns restored hasher and the stored stream position.
+///
+/// # Errors
+/// If the checkpoint is malformed or corrupted, was created for a different
+/// algorithm, or its state version is not equal to [`StateVersion::STATE_VERSION`] of `D`.
+pub fn restore<D>(checkpoint: &[u8]) -> Result<(D, u64), CheckpointError>
+where
+    D: StateVersion + AlgorithmName,
+{
+    Parsed::new(checkpoint)?.restore()
+}
+
+/// Restore hasher state from `checkpoint` created by [`save_with_oid`].
+///
+/// In addition to the checks performed by [`restore`], it checks that the stored
+/// OID is equal to the OID of `D`.
+///
+/// # Errors
+/// If the checkpoint is malformed or corrupted, was created for a different
+/// algorithm or without OID, or its state version is not equal to
+/// [`StateVersion::STATE_VERSION`] of `D`.
+#[cfg(feature = "oid")]
+pub fn restore_with_oid<D>(checkpoint: &[u8]) -> Result<(D, u64), CheckpointError>
+where
+    D: StateVersion + AlgorithmName + AssociatedOid,
+{
+    let parsed = Parsed::new(checkpoint)?;
+    if parsed.oid != D::OID.as_bytes() {
+        return Err(CheckpointError::AlgorithmMismatch);
+    }
+    parsed.restore()
+}
+
+fn save_inner<D>(hasher: &D, oid: &[u8], position: u64) -> Vec<u8>
+where
+    D: StateVersion + AlgorithmName,
+{
+    let version = D::STATE_VERSION;
+    let version_len = u8::try_from(version.len()).expect("state version is too long");
+    let name = alg_name::<D>();
+    let name_len = u8::try_from(name.len()).expect("algorithm name is too long");
+    let oid_len = u8::try_from(oid.len()).expect("OID is too long");
+    let state = hasher.serialize();
+    let state_len = u32::try_from(state.len()).expect("serialized state is too long");
+
+    let var_len = version.len() + name.len() + oid.len() + state.len();
+    let mut buf = Vec::with_capacity(HEADER_LEN + var_len + CHECKSUM_LEN);
+    buf.extend_from_slice(&MAGIC);
+    buf.push(FORMAT_VERSION);
+    buf.push(version_len);
+    buf.extend_from_slice(version.as_bytes());
+    buf.push(name_len);
+    buf.extend_from_slice(name.as_bytes());
+    buf.push(oid_len);
+    buf.extend_from_slice(oid);
+    buf.extend_from_slice(&position.to_le_bytes());
+    buf.extend_from_slice(&state_len.to_le_bytes());
+    buf.extend_from_slice(&state);
+    let checksum = crc32(&buf);
+    buf.extend_from_slice(&checksum.to_le_bytes());
+    buf
+}
+
+/// Checkpoint fields.
+struct Parsed<'a> {
+    version: &'a [u8],
+    name: &'a [u8],
+    #[cfg_attr(not(feature = "oid"), allow(dead_code))]
+    oid: &'a [u8],
+    position: u64,
+    state: &'a [u8],
+}
+
+impl<'a> Parsed<'a> {
+    fn new(checkpoint: &'a [u8]) -> Result<Self, CheckpointError> {
+        let (data, checksum) = checkpoint
+            .split_last_chunk::<CHECKSUM_LEN>()
+            .ok_or(CheckpointError::Malformed)?;
+        let mut reader = Reader(data);
+        if reader.take_array::<4>()? != MAGIC {
+            return Err(CheckpointError::Malformed);
+        }
+        if reader.take_array::<1>()? != [FORMAT_VERSION] {
+            return Err(CheckpointError::UnsupportedFormat);
+        }
+        if crc32(data) != u32::from_le_bytes(*checksum) {
+            return Err(CheckpointError::ChecksumMismatch);
+        }
+
+        let [version_len] = reader.take_array()?;
+        let version = reader.take(version_len.into())?;
+        let [name_len] = reader.take_array()?;
+        let name = reader.take(name_len.into())?;
+        let [oid_len] = reader.take_array()?;
+        let oid = reader.take(oid_len.into())?;
+        let position = u64::from_le_bytes(reader.take_array()?);
+        let state_len = u32::from_le_bytes(reader.take_array()?);
+        let state_len = usize::try_from(state_len).map_err(|_| CheckpointError::Malformed)?;
+        let state = reader.take(state_len)?;
+        if !reader.0.is_empty() {
+            return Err(CheckpointError::Malformed);
+        }
+        Ok(Self {
+            version,
+            name,
+            oid,
+            position,
+            state,
+        })
+    }
+
+    fn restore<D>(self) -> Result<(D, u64), CheckpointError>
+    where
+        D: StateVersion + AlgorithmName,
+    {
+        if self.name != alg_name::<D>().as_bytes() {
+            return Err(CheckpointError::AlgorithmMismatch);
+        }
+        if self.state.len() != D::SerializedStateSize::USIZE {
+            return Err(CheckpointError::StateSizeMismatch);
+        }
+        if self.version != D::STATE_VERSION.as_bytes() {
+            return Err(CheckpointError::StateVersionMismatch);
+        }
+        let state = SerializedState::<D>::try_from(self.state)
+            .map_err(|_| CheckpointError::InvalidState)?;
+        let hasher = D::deserialize(&state).map_err(|_| CheckpointError::InvalidState)?;
+        Ok((hasher, self.position))
+    }
+}
+
+/// Checkpoint restoration error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CheckpointError {
+    /// Checkpoint is truncated or has invalid structure.
+    Malformed,
+    /// Checkpoint uses unsupported container format version.
+    UnsupportedFormat,
+    /// Checksum of the checkpoint does not match its contents.
+    ChecksumMismatch,
+    /// Checkpoint was created for a different algorithm.
+    AlgorithmMismatch,
+    /// Length of the stored state is not equal to the serialized state size of the hasher.
+    StateSizeMismatch,
+    /// Checkpoint uses a different state format version.
+    StateVersionMismatch,
+    /// Stored state can not be deserialized.
+    InvalidState,
+}
+
+impl fmt::Display for CheckpointError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Malformed => "malformed checkpoint",
+            Self::UnsupportedFormat => "unsupported checkpoint format",
+            Self::ChecksumMismatch => "checkpoint checksum mismatch",
+            Self::AlgorithmMismatch => "checkpoint was created for a different algorithm",
+            Self::StateSizeMismatch => "checkpoint state size mismatch",
+            Self::StateVersionMismatch => "checkpoint state version mismatch",
+            Self::InvalidState => "invalid serialized state in checkpoint",
+        })
+    }
+}
+
+impl core::error::Error for CheckpointError {}
+
+/// Helper for reading checkpoint fields.
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], CheckpointError> {
+        let (head, tail) = self
+            .0
+            .split_at_checked(n)
+            .ok_or(CheckpointError::Malformed)?;
+        self.0 = tail;
+        Ok(head)
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N], CheckpointError> {
+        let (head, tail) = self
+            .0
+            .split_first_chunk::<N>()
+            .ok_or(CheckpointError::Malformed)?;
+        self.0 = tail;
+        Ok(*head)
+    }
+}
+
+/// Get algorithm name of `D`.
+pub(crate) fn alg_name<D: AlgorithmName>() -> alloc::string::String {
+    struct Name<D>(PhantomData<D>);
+
+    impl<D: AlgorithmName> fmt::Display for Name<D> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            D::write_alg_name(f)
+        }
+    }
+
+    Name::<D>(PhantomData).to_string()
+}
+
+/// Compute CRC-32 (IEEE 802.3) checksum of `data`.
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &b in data {
+        crc ^= u32::from(b);
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
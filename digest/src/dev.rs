@@ -4,13 +4,19 @@
 
 pub use blobby;
 
+#[cfg(feature = "alloc")]
+mod cavp;
 mod fixed;
+mod incremental;
 #[cfg(feature = "mac")]
 mod mac;
 mod rng;
 mod xof;
 
+#[cfg(feature = "alloc")]
+pub use cavp::*;
 pub use fixed::*;
+pub use incremental::*;
 #[cfg(feature = "mac")]
 pub use mac::*;
 pub use xof::*;
@@ -51,6 +57,25 @@ macro_rules! new_test {
     };
 }
 
+/// Define hash function test using a NIST CAVP response file
+///
+/// `$test_fn` is one of the CAVP test functions, e.g. [`cavp_msg_test`] or
+/// [`cavp_sha2_monte_test`], and `$file_name` is the name of the `.rsp` file
+/// inside the `data` directory without the extension.
+#[cfg(feature = "alloc")]
+#[macro_export]
+macro_rules! cavp_test {
+    ($name:ident, $hasher:ty, $test_fn:ident, $file_name:expr $(,)?) => {
+        #[test]
+        fn $name() {
+            let rsp = include_str!(concat!("data/", $file_name, ".rsp"));
+            if let Err(err) = $crate::dev::$test_fn::<$hasher>(rsp) {
+                panic!("\nFailed CAVP test {}: {err}\n", $file_name);
+            }
+        }
+    };
+}
+
 /// Define hash function serialization test
 #[macro_export]
 macro_rules! hash_serialization_test {
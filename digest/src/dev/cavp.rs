@@ -0,0 +1,360 @@
+//! Test runners for NIST CAVP response (`.rsp`) files.
+
+use crate::{Digest, ExtendableOutput};
+use alloc::vec::Vec;
+use core::fmt;
+
+/// Number of iterations in one Monte Carlo test checkpoint.
+const MCT_ITERATIONS: usize = 1000;
+
+/// Error returned by CAVP test functions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CavpError {
+    /// Number of the line (starting from 1) at which the failed record starts.
+    pub line: usize,
+    /// Failure reason.
+    pub reason: &'static str,
+}
+
+impl CavpError {
+    fn new(line: usize, reason: &'static str) -> Self {
+        Self { line, reason }
+    }
+}
+
+impl fmt::Display for CavpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.reason)
+    }
+}
+
+impl core::error::Error for CavpError {}
+
+/// Record of a CAVP response file.
+#[derive(Clone, Debug)]
+pub struct CavpRecord<'a> {
+    /// Number of the line (starting from 1) at which the record starts.
+    pub line: usize,
+    /// Parameters from the preceding section headers, e.g. `[L = 32]`.
+    ///
+    /// Headers without `=` (e.g. `[Tested for Output of byte-oriented messages]`)
+    /// are stored with an empty value.
+    pub params: Vec<(&'a str, &'a str)>,
+    /// Record fields, e.g. `Len = 8`.
+    pub fields: Vec<(&'a str, &'a str)>,
+}
+
+impl<'a> CavpRecord<'a> {
+    /// Get value of the field `name`.
+    #[must_use]
+    pub fn field(&self, name: &str) -> Option<&'a str> {
+        find(&self.fields, name)
+    }
+
+    /// Get value of the section parameter `name`.
+    #[must_use]
+    pub fn param(&self, name: &str) -> Option<&'a str> {
+        find(&self.params, name)
+    }
+
+    /// Decode hex-encoded value of the field `name`.
+    pub fn hex_field(&self, name: &str) -> Result<Vec<u8>, CavpError> {
+        let val = self.field(name).ok_or(self.err("missing field"))?;
+        decode_hex(val).ok_or(self.err("invalid hex value"))
+    }
+
+    /// Parse decimal value of the field `name`.
+    pub fn num_field(&self, name: &str) -> Result<usize, CavpError> {
+        let val = self.field(name).ok_or(self.err("missing field"))?;
+        val.parse().map_err(|_| self.err("invalid number"))
+    }
+
+    /// Parse decimal value of the section parameter `name`.
+    pub fn num_param(&self, name: &str) -> Result<usize, CavpError> {
+        let val = self.param(name).ok_or(self.err("missing parameter"))?;
+        val.parse().map_err(|_| self.err("invalid number"))
+    }
+
+    fn err(&self, reason: &'static str) -> CavpError {
+        CavpError::new(self.line, reason)
+    }
+
+    /// Get message defined by `Msg` and optional `Len` (in bits) fields.
+    fn msg(&self) -> Result<Vec<u8>, CavpError> {
+        let mut msg = self.hex_field("Msg")?;
+        if self.field("Len").is_some() {
+            let len = self.num_field("Len")?;
+            if len % 8 != 0 {
+                return Err(self.err("bit-oriented messages are not supported"));
+            }
+            // Empty messages are encoded as `Msg = 00`
+            msg.truncate(len / 8);
+            if msg.len() != len / 8 {
+                return Err(self.err("message is shorter than `Len`"));
+            }
+        }
+        Ok(msg)
+    }
+}
+
+/// Parse CAVP response file.
+///
+/// Lines starting with `#` are ignored. Every record consists of consecutive
+/// `name = value` lines and is terminated by an empty line or a section header.
+pub fn parse_cavp(rsp: &str) -> Result<Vec<CavpRecord<'_>>, CavpError> {
+    let mut records = Vec::new();
+    let mut params = Vec::new();
+    let mut fields = Vec::new();
+    let mut record_line = 0;
+    let mut new_section = true;
+
+    for (i, line) in rsp.lines().enumerate() {
+        let line_num = i + 1;
+        let line = line.trim();
+        let is_field = !(line.is_empty() || line.starts_with('#') || line.starts_with('['));
+        if !is_field && !fields.is_empty() {
+            records.push(CavpRecord {
+                line: record_line,
+                params: params.clone(),
+                fields: core::mem::take(&mut fields),
+            });
+            new_section = true;
+        }
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(header) = line.strip_prefix('[') {
+            let header = header
+                .strip_suffix(']')
+                .ok_or(CavpError::new(line_num, "unterminated section header"))?;
+            if new_section {
+                params.clear();
+                new_section = false;
+            }
+            params.push(split_pair(header).unwrap_or((header.trim(), "")));
+            continue;
+        }
+
+        let pair = split_pair(line).ok_or(CavpError::new(line_num, "invalid line"))?;
+        if fields.is_empty() {
+            record_line = line_num;
+        }
+        fields.push(pair);
+    }
+
+    if !fields.is_empty() {
+        records.push(CavpRecord {
+            line: record_line,
+            params,
+            fields,
+        });
+    }
+    Ok(records)
+}
+
+/// Run tests from `ShortMsg.rsp` and `LongMsg.rsp` files for fixed-output hashes
+/// (e.g. `SHA256ShortMsg.rsp` or `SHA3_256LongMsg.rsp`).
+pub fn cavp_msg_test<D: Digest>(rsp: &str) -> Result<(), CavpError> {
+    let records = test_records(rsp, &["Msg", "MD"])?;
+    for rec in records {
+        let msg = rec.msg()?;
+        let expected = rec.hex_field("MD")?;
+        if expected.len() != <D as Digest>::output_size() {
+            return Err(rec.err("output size mismatch"));
+        }
+        if D::digest(&msg)[..] != expected[..] {
+            return Err(rec.err("whole message"));
+        }
+        let mut hasher = D::new();
+        for chunk in msg.chunks(17) {
+            hasher.update(chunk);
+        }
+        if hasher.finalize()[..] != expected[..] {
+            return Err(rec.err("message in chunks"));
+        }
+    }
+    Ok(())
+}
+
+/// Run tests from `ShortMsg.rsp`, `LongMsg.rsp` and `VariableOut.rsp` files for
+/// extendable-output functions (e.g. `SHAKE128ShortMsg.rsp`).
+///
+/// Output length is defined by length of the `Output` field.
+pub fn cavp_xof_test<D: ExtendableOutput + Default>(rsp: &str) -> Result<(), CavpError> {
+    let records = test_records(rsp, &["Msg", "Output"])?;
+    for rec in records {
+        let msg = rec.msg()?;
+        let expected = rec.hex_field("Output")?;
+        if rec.field("Outputlen").is_some() && rec.num_field("Outputlen")? != 8 * expected.len() {
+            return Err(rec.err("`Outputlen` does not match length of `Output`"));
+        }
+        let mut buf = alloc::vec![0u8; expected.len()];
+        let mut hasher = D::default();
+        hasher.update(&msg);
+        hasher.finalize_xof_into(&mut buf);
+        if buf != expected {
+            return Err(rec.err("whole message"));
+        }
+    }
+    Ok(())
+}
+
+/// Run SHA-1 and SHA-2 Monte Carlo test from a `Monte.rsp` file
+/// (e.g. `SHA256Monte.rsp`).
+///
+/// For every checkpoint `MD_0 = MD_1 = MD_2 = Seed`, `MD_i = H(MD_{i-3} || MD_{i-2} || MD_{i-1})`
+/// for `i` in `3..1003`, and the checkpoint result `MD_1002` becomes the next seed.
+pub fn cavp_sha2_monte_test<D: Digest>(rsp: &str) -> Result<(), CavpError> {
+    monte_test(rsp, "Seed", "MD", |rec, seed| {
+        if seed.len() != <D as Digest>::output_size() {
+            return Err(rec.err("seed size mismatch"));
+        }
+        let mut mds = [seed.clone(), seed.clone(), seed.clone()];
+        for _ in 0..MCT_ITERATIONS {
+            let md = D::new()
+                .chain_update(&mds[0])
+                .chain_update(&mds[1])
+                .chain_update(&mds[2])
+                .finalize();
+            mds.rotate_left(1);
+            mds[2].clear();
+            mds[2].extend_from_slice(&md);
+        }
+        let [_, _, md] = mds;
+        *seed = md;
+        Ok(())
+    })
+}
+
+/// Run SHA-3 Monte Carlo test from a `Monte.rsp` file (e.g. `SHA3_256Monte.rsp`).
+///
+/// For every checkpoint `MD_0 = Seed` and `MD_i = H(MD_{i-1})` for `i` in `1..1001`,
+/// and the checkpoint result `MD_1000` becomes the next seed.
+pub fn cavp_sha3_monte_test<D: Digest>(rsp: &str) -> Result<(), CavpError> {
+    monte_test(rsp, "Seed", "MD", |_, seed| {
+        for _ in 0..MCT_ITERATIONS {
+            let md = D::digest(&*seed);
+            seed.clear();
+            seed.extend_from_slice(&md);
+        }
+        Ok(())
+    })
+}
+
+/// Run SHAKE Monte Carlo test from a `Monte.rsp` file (e.g. `SHAKE128Monte.rsp`).
+///
+/// Minimum and maximum output lengths are read from the section parameters.
+/// For every iteration the message is equal to the leftmost 128 bits of
+/// the previous output and length of the next output is computed from
+/// the rightmost 16 bits of the current output.
+pub fn cavp_shake_monte_test<D: ExtendableOutput + Default>(rsp: &str) -> Result<(), CavpError> {
+    let mut out_len = None;
+    monte_test(rsp, "Msg", "Output", |rec, output| {
+        let min_len = rec.num_param("Minimum Output Length (bits)")? / 8;
+        let max_len = rec.num_param("Maximum Output Length (bits)")? / 8;
+        if min_len == 0 || max_len < min_len {
+            return Err(rec.err("invalid output length range"));
+        }
+        let range = max_len - min_len + 1;
+        let out_len = out_len.get_or_insert(max_len);
+
+        for _ in 0..MCT_ITERATIONS {
+            let mut msg = [0u8; 16];
+            let n = output.len().min(msg.len());
+            msg[..n].copy_from_slice(&output[..n]);
+
+            output.resize(*out_len, 0);
+            let mut hasher = D::default();
+            hasher.update(&msg);
+            hasher.finalize_xof_into(output);
+
+            let [.., a, b] = output[..] else {
+                return Err(rec.err("output is too short"));
+            };
+            *out_len = min_len + usize::from(u16::from_be_bytes([a, b])) % range;
+        }
+
+        if rec.field("Outputlen").is_some() && rec.num_field("Outputlen")? != 8 * output.len() {
+            return Err(rec.err("output length mismatch"));
+        }
+        Ok(())
+    })
+}
+
+/// Run Monte Carlo test.
+///
+/// `seed_field` is used to find the initial seed, `checkpoint` computes next checkpoint
+/// value from the previous one, and the result is compared against `out_field`
+/// of the checkpoint records.
+fn monte_test(
+    rsp: &str,
+    seed_field: &str,
+    out_field: &str,
+    mut checkpoint: impl FnMut(&CavpRecord<'_>, &mut Vec<u8>) -> Result<(), CavpError>,
+) -> Result<(), CavpError> {
+    let mut seed = None;
+    let mut count = 0;
+    for rec in parse_cavp(rsp)? {
+        if rec.field(seed_field).is_some() && rec.field("COUNT").is_none() {
+            seed = Some(rec.hex_field(seed_field)?);
+            continue;
+        }
+        if rec.field("COUNT").is_none() {
+            continue;
+        }
+        let seed = seed.as_mut().ok_or(rec.err("missing seed"))?;
+        if rec.num_field("COUNT")? != count {
+            return Err(rec.err("unexpected `COUNT` value"));
+        }
+        checkpoint(&rec, seed)?;
+        if *seed != rec.hex_field(out_field)? {
+            return Err(rec.err("Monte Carlo checkpoint mismatch"));
+        }
+        count += 1;
+    }
+    if count == 0 {
+        return Err(CavpError::new(0, "no test records"));
+    }
+    Ok(())
+}
+
+/// Parse `rsp` and select records which contain all `fields`.
+fn test_records<'a>(rsp: &'a str, fields: &[&str]) -> Result<Vec<CavpRecord<'a>>, CavpError> {
+    let records: Vec<_> = parse_cavp(rsp)?
+        .into_iter()
+        .filter(|rec| fields.iter().all(|&f| rec.field(f).is_some()))
+        .collect();
+    if records.is_empty() {
+        return Err(CavpError::new(0, "no test records"));
+    }
+    Ok(records)
+}
+
+fn find<'a>(pairs: &[(&'a str, &'a str)], name: &str) -> Option<&'a str> {
+    pairs.iter().find(|(n, _)| *n == name).map(|&(_, v)| v)
+}
+
+fn split_pair(s: &str) -> Option<(&str, &str)> {
+    let (name, val) = s.split_once('=')?;
+    Some((name.trim(), val.trim()))
+}
+
+fn decode_hex(s: &str) -> Option<Vec<u8>> {
+    fn nibble(c: u8) -> Option<u8> {
+        match c {
+            b'0'..=b'9' => Some(c - b'0'),
+            b'a'..=b'f' => Some(c - b'a' + 10),
+            b'A'..=b'F' => Some(c - b'A' + 10),
+            _ => None,
+        }
+    }
+
+    let chunks = s.as_bytes().chunks_exact(2);
+    if !chunks.remainder().is_empty() {
+        return None;
+    }
+    chunks
+        .map(|pair| Some((nibble(pair[0])? << 4) | nibble(pair[1])?))
+        .collect()
+}
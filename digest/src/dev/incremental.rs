@@ -0,0 +1,364 @@
+//! Checks that results do not depend on how input data is split into updates.
+//!
+//! The checks feed pseudorandom messages with lengths around block boundaries
+//! to the tested type using different split patterns and compare the results
+//! against processing of the whole message at once.
+use crate::{
+    Digest, ExtendableOutput, ExtendableOutputReset, FixedOutputReset, Reset, Update, XofReader,
+    common::{BlockSizeUser, hazmat::SerializableState, typenum::Unsigned},
+    dev::rng,
+};
+#[cfg(feature = "mac")]
+use crate::{Mac, common::KeyInit};
+
+/// Size of the pseudorandom message buffer.
+const MSG_BUF_LEN: usize = 1024;
+/// Size of XOF output used in checks.
+const XOF_OUT_LEN: usize = 512;
+
+/// Check that hashing results do not depend on how the message is split into updates.
+///
+/// Messages are processed byte by byte, in block-aligned and misaligned chunks,
+/// with interleaved empty updates, and split at every possible point. The check
+/// also clones hasher in the middle of the stream and compares results of
+/// both instances.
+pub fn incremental_digest_test<D: Digest + Clone + BlockSizeUser>() -> Result<(), &'static str> {
+    split_test(
+        D::BlockSize::USIZE,
+        D::new,
+        |h, data| Digest::update(h, data),
+        Digest::finalize,
+    )
+}
+
+/// Check that [`Reset`] and [`FixedOutputReset`] implementations work correctly
+/// at any point of the stream.
+pub fn incremental_digest_reset_test<D>() -> Result<(), &'static str>
+where
+    D: Digest + FixedOutputReset + Reset + Clone + BlockSizeUser,
+{
+    reset_test(
+        D::BlockSize::USIZE,
+        D::new,
+        |h, data| Digest::update(h, data),
+        Digest::finalize,
+        |h| Digest::finalize_reset(h),
+        |h| Digest::reset(h),
+    )
+}
+
+/// Check that [`SerializableState`] round-trips preserve hasher state
+/// at any point of the stream.
+pub fn incremental_serialization_test<D>() -> Result<(), &'static str>
+where
+    D: Digest + SerializableState + BlockSizeUser,
+{
+    serialization_test(
+        D::BlockSize::USIZE,
+        D::new,
+        |h, data| Digest::update(h, data),
+        Digest::finalize,
+    )
+}
+
+/// Check that MAC results do not depend on how the message is split into updates.
+///
+/// See [`incremental_digest_test`] for the list of used split patterns.
+#[cfg(feature = "mac")]
+pub fn incremental_mac_test<M: Mac + KeyInit + Clone + BlockSizeUser>() -> Result<(), &'static str>
+{
+    let mac = new_mac::<M>()?;
+    split_test(
+        M::BlockSize::USIZE,
+        || mac.clone(),
+        |m, data| Mac::update(m, data),
+        |m| m.finalize().into_bytes(),
+    )
+}
+
+/// Check that MAC [`Reset`] and [`FixedOutputReset`] implementations work correctly
+/// at any point of the stream.
+#[cfg(feature = "mac")]
+pub fn incremental_mac_reset_test<M>() -> Result<(), &'static str>
+where
+    M: Mac + KeyInit + FixedOutputReset + Reset + Clone + BlockSizeUser,
+{
+    let mac = new_mac::<M>()?;
+    reset_test(
+        M::BlockSize::USIZE,
+        || mac.clone(),
+        |m, data| Mac::update(m, data),
+        |m| m.finalize().into_bytes(),
+        |m| Mac::finalize_reset(m).into_bytes(),
+        |m| Mac::reset(m),
+    )
+}
+
+/// Check that [`SerializableState`] round-trips preserve MAC state
+/// at any point of the stream.
+#[cfg(feature = "mac")]
+pub fn incremental_mac_serialization_test<M>() -> Result<(), &'static str>
+where
+    M: Mac + KeyInit + SerializableState + Clone + BlockSizeUser,
+{
+    let mac = new_mac::<M>()?;
+    serialization_test(
+        M::BlockSize::USIZE,
+        || mac.clone(),
+        |m, data| Mac::update(m, data),
+        |m| m.finalize().into_bytes(),
+    )
+}
+
+/// Check that XOF results do not depend on how the message is split into updates
+/// and how the output is read.
+///
+/// See [`incremental_digest_test`] for the list of used split patterns.
+pub fn incremental_xof_test<D>() -> Result<(), &'static str>
+where
+    D: ExtendableOutput + Default + Clone + BlockSizeUser,
+{
+    let block_size = D::BlockSize::USIZE.max(1);
+    split_test(block_size, D::default, Update::update, xof_finalize)?;
+
+    let msg_buf = msg_buf();
+    let expected = xof_finalize(D::default().chain(&msg_buf[..]));
+    for n in [1, block_size - 1, block_size, block_size + 1] {
+        let n = n.max(1);
+        let mut reader = D::default().chain(&msg_buf[..]).finalize_xof();
+        let mut out = [0u8; XOF_OUT_LEN];
+        for chunk in out.chunks_mut(n) {
+            reader.read(&mut []);
+            reader.read(chunk);
+        }
+        if out != expected {
+            return Err("output read in chunks");
+        }
+    }
+    Ok(())
+}
+
+/// Check that XOF [`Reset`] and [`ExtendableOutputReset`] implementations work correctly
+/// at any point of the stream.
+pub fn incremental_xof_reset_test<D>() -> Result<(), &'static str>
+where
+    D: ExtendableOutputReset + Reset + Default + BlockSizeUser,
+{
+    reset_test(
+        D::BlockSize::USIZE,
+        D::default,
+        Update::update,
+        xof_finalize,
+        |h| {
+            let mut out = [0u8; XOF_OUT_LEN];
+            h.finalize_xof_reset_into(&mut out);
+            out
+        },
+        Reset::reset,
+    )
+}
+
+/// Check that [`SerializableState`] round-trips preserve XOF state
+/// at any point of the stream.
+pub fn incremental_xof_serialization_test<D>() -> Result<(), &'static str>
+where
+    D: ExtendableOutput + SerializableState + Default + BlockSizeUser,
+{
+    serialization_test(
+        D::BlockSize::USIZE,
+        D::default,
+        Update::update,
+        xof_finalize,
+    )
+}
+
+/// Initialize MAC with a pseudorandom key.
+#[cfg(feature = "mac")]
+fn new_mac<M: Mac + KeyInit>() -> Result<M, &'static str> {
+    let mut key_buf = [0u8; MSG_BUF_LEN];
+    let mut rng = rng::RNG;
+    // the first output of the RNG is equal to `msg_buf`, so skip it
+    // to make the key independent from the processed messages
+    rng.fill(&mut key_buf);
+    rng.fill(&mut key_buf);
+    let Some(key) = key_buf.get(..M::key_size()) else {
+        return Err("key is too long");
+    };
+    <M as KeyInit>::new_from_slice(key).map_err(|_| "failed to initialize MAC instance")
+}
+
+/// Read [`XOF_OUT_LEN`] bytes of XOF output.
+fn xof_finalize<D: ExtendableOutput>(h: D) -> [u8; XOF_OUT_LEN] {
+    let mut out = [0u8; XOF_OUT_LEN];
+    h.finalize_xof_into(&mut out);
+    out
+}
+
+/// Run reset checks using the provided initialization, update, finalization,
+/// and reset functions.
+fn reset_test<S, R: PartialEq>(
+    block_size: usize,
+    new: impl Fn() -> S,
+    update: impl Fn(&mut S, &[u8]),
+    finalize: impl Fn(S) -> R,
+    finalize_reset: impl Fn(&mut S) -> R,
+    reset: impl Fn(&mut S),
+) -> Result<(), &'static str> {
+    let process = |data: &[u8]| {
+        let mut state = new();
+        update(&mut state, data);
+        finalize(state)
+    };
+
+    let msg_buf = msg_buf();
+    for len in msg_lens(block_size) {
+        let msg = &msg_buf[..len];
+        let expected = process(msg);
+        for pos in 0..=len {
+            let (head, tail) = msg.split_at(pos);
+
+            let mut state = new();
+            update(&mut state, head);
+            reset(&mut state);
+            update(&mut state, msg);
+            if finalize(state) != expected {
+                return Err("reset in the middle of the stream");
+            }
+
+            let mut state = new();
+            update(&mut state, head);
+            if finalize_reset(&mut state) != process(head) {
+                return Err("finalize_reset in the middle of the stream");
+            }
+            update(&mut state, head);
+            update(&mut state, tail);
+            if finalize_reset(&mut state) != expected {
+                return Err("update after finalize_reset");
+            }
+            if finalize(state) != process(b"") {
+                return Err("finalize after finalize_reset");
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Run serialization checks using the provided initialization, update,
+/// and finalization functions.
+fn serialization_test<S: SerializableState, R: PartialEq>(
+    block_size: usize,
+    new: impl Fn() -> S,
+    update: impl Fn(&mut S, &[u8]),
+    finalize: impl Fn(S) -> R,
+) -> Result<(), &'static str> {
+    let msg_buf = msg_buf();
+    for len in msg_lens(block_size) {
+        let msg = &msg_buf[..len];
+        let mut state = new();
+        update(&mut state, msg);
+        let expected = finalize(state);
+        for pos in 0..=len {
+            let (head, tail) = msg.split_at(pos);
+            let mut state = new();
+            update(&mut state, head);
+
+            let serialized = state.serialize();
+            let Ok(mut state) = S::deserialize(&serialized) else {
+                return Err("failed to deserialize state");
+            };
+            if state.serialize() != serialized {
+                return Err("serialized state round-trip");
+            }
+            update(&mut state, tail);
+            if finalize(state) != expected {
+                return Err("update after deserialization");
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Run split pattern checks using the provided initialization, update, and
+/// finalization functions.
+fn split_test<S: Clone, R: PartialEq>(
+    block_size: usize,
+    new: impl Fn() -> S,
+    update: impl Fn(&mut S, &[u8]),
+    finalize: impl Fn(S) -> R,
+) -> Result<(), &'static str> {
+    let process = |chunks: &mut dyn Iterator<Item = &[u8]>| {
+        let mut state = new();
+        for chunk in chunks {
+            update(&mut state, chunk);
+        }
+        finalize(state)
+    };
+
+    let msg_buf = msg_buf();
+    let bs = block_size.max(1);
+    for len in msg_lens(bs) {
+        let msg = &msg_buf[..len];
+        let expected = process(&mut core::iter::once(msg));
+
+        for chunk_size in [1, bs - 1, bs, bs + 1, 2 * bs - 1, 2 * bs + 1] {
+            let chunk_size = chunk_size.max(1);
+            if process(&mut msg.chunks(chunk_size)) != expected {
+                return Err("message in chunks");
+            }
+        }
+
+        for offset in [1, bs - 1, bs + 1] {
+            let (head, tail) = msg.split_at(offset.min(len));
+            if process(&mut core::iter::once(head).chain(tail.chunks(bs))) != expected {
+                return Err("message in misaligned chunks");
+            }
+        }
+
+        let chunks = msg.chunks(bs).flat_map(|chunk| [&[][..], chunk]);
+        if process(&mut chunks.chain([&[][..]])) != expected {
+            return Err("message with empty updates");
+        }
+
+        for pos in 0..=len {
+            let (head, tail) = msg.split_at(pos);
+            let mut state = new();
+            update(&mut state, head);
+            let mut state2 = state.clone();
+            update(&mut state, tail);
+            if finalize(state) != expected {
+                return Err("message split in two parts");
+            }
+            update(&mut state2, tail);
+            if finalize(state2) != expected {
+                return Err("state cloned in the middle of the stream");
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Generate pseudorandom message buffer.
+fn msg_buf() -> [u8; MSG_BUF_LEN] {
+    let mut buf = [0u8; MSG_BUF_LEN];
+    let mut rng = rng::RNG;
+    rng.fill(&mut buf);
+    buf
+}
+
+/// Get message lengths around block boundaries.
+fn msg_lens(block_size: usize) -> impl Iterator<Item = usize> {
+    let bs = block_size.max(1);
+    [
+        0,
+        1,
+        bs - 1,
+        bs,
+        bs + 1,
+        2 * bs - 1,
+        2 * bs,
+        2 * bs + 1,
+        3 * bs + 7,
+    ]
+    .into_iter()
+    .map(|len| len.min(MSG_BUF_LEN))
+}
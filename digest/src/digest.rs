@@ -1,6 +1,11 @@
-use super::{FixedOutput, FixedOutputReset, InvalidBufferSize, Reset, Update};
+use super::{
+    ExtendableOutput, ExtendableOutputReset, FixedOutput, FixedOutputReset, InvalidBufferSize,
+    Reset, Update,
+};
 use common::{Output, OutputSizeUser, typenum::Unsigned};
 
+#[cfg(feature = "alloc")]
+use super::XofReader;
 #[cfg(feature = "alloc")]
 use alloc::boxed::Box;
 #[cfg(feature = "oid")]
@@ -54,6 +59,33 @@ pub trait Digest: OutputSizeUser {
 
     /// Compute hash of `data`.
     fn digest(data: impl AsRef<[u8]>) -> Output<Self>;
+
+    /// Process data read from `reader` until EOF, updating the internal state.
+    ///
+    /// Returns number of processed bytes.
+    ///
+    /// # Errors
+    /// If reading from `reader` fails.
+    #[cfg(feature = "std")]
+    #[inline]
+    fn update_reader(&mut self, reader: impl std::io::Read) -> std::io::Result<u64> {
+        crate::io::read_chunks(reader, |chunk| Digest::update(self, chunk))
+    }
+
+    /// Compute hash of data read from `reader` until EOF.
+    ///
+    /// # Errors
+    /// If reading from `reader` fails.
+    #[cfg(feature = "std")]
+    #[inline]
+    fn digest_reader(reader: impl std::io::Read) -> std::io::Result<Output<Self>>
+    where
+        Self: Sized,
+    {
+        let mut hasher = Self::new();
+        hasher.update_reader(reader)?;
+        Ok(hasher.finalize())
+    }
 }
 
 impl<D: FixedOutput + Default + Update + HashMarker> Digest for D {
@@ -230,6 +262,72 @@ impl Clone for Box<dyn DynDigest> {
     }
 }
 
+/// Modification of the [`ExtendableOutput`] trait suitable for trait objects.
+pub trait DynXof {
+    /// Digest input data.
+    ///
+    /// This method can be called repeatedly for use with streaming messages.
+    fn update(&mut self, data: &[u8]);
+
+    /// Retrieve boxed XOF reader and consume boxed hasher instance.
+    #[cfg(feature = "alloc")]
+    fn finalize_xof_boxed(self: Box<Self>) -> Box<dyn XofReader>;
+
+    /// Retrieve boxed XOF reader and reset hasher instance.
+    #[cfg(feature = "alloc")]
+    fn finalize_xof_reset_boxed(&mut self) -> Box<dyn XofReader>;
+
+    /// Write result into `out` and reset hasher instance.
+    fn finalize_xof_reset_into(&mut self, out: &mut [u8]);
+
+    /// Reset hasher instance to its initial state.
+    fn reset(&mut self);
+
+    /// Clone hasher state into a boxed trait object
+    #[cfg(feature = "alloc")]
+    fn box_clone(&self) -> Box<dyn DynXof>;
+}
+
+impl<D> DynXof for D
+where
+    D: ExtendableOutput + ExtendableOutputReset + Clone + 'static,
+    D::Reader: 'static,
+{
+    fn update(&mut self, data: &[u8]) {
+        Update::update(self, data);
+    }
+
+    #[cfg(feature = "alloc")]
+    fn finalize_xof_boxed(self: Box<Self>) -> Box<dyn XofReader> {
+        Box::new(ExtendableOutput::finalize_xof(*self))
+    }
+
+    #[cfg(feature = "alloc")]
+    fn finalize_xof_reset_boxed(&mut self) -> Box<dyn XofReader> {
+        Box::new(ExtendableOutputReset::finalize_xof_reset(self))
+    }
+
+    fn finalize_xof_reset_into(&mut self, out: &mut [u8]) {
+        ExtendableOutputReset::finalize_xof_reset_into(self, out);
+    }
+
+    fn reset(&mut self) {
+        Reset::reset(self);
+    }
+
+    #[cfg(feature = "alloc")]
+    fn box_clone(&self) -> Box<dyn DynXof> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Clone for Box<dyn DynXof> {
+    fn clone(&self) -> Self {
+        self.box_clone()
+    }
+}
+
 /// Convenience wrapper trait around [DynDigest] and [DynAssociatedOid].
 #[cfg(feature = "oid")]
 pub trait DynDigestWithOid: DynDigest + DynAssociatedOid {}
@@ -0,0 +1,218 @@
+//! Injective encodings for hashing of structured data.
+//!
+//! Simple concatenation of fields is ambiguous, e.g. `("ab", "c")` and `("a", "bc")`
+//! produce the same hash input. This module implements length-prefixed encodings
+//! defined in [NIST SP 800-185] which allow to unambiguously hash tuples and lists
+//! of byte strings using any [`Update`] implementation.
+//!
+//! [NIST SP 800-185]: https://doi.org/10.6028/NIST.SP.800-185
+
+use crate::Update;
+
+/// Maximum length of encoded integer.
+const MAX_ENCODED_LEN: usize = 9;
+
+/// Integer encoded using [`left_encode`] or [`right_encode`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EncodedInt {
+    buf: [u8; MAX_ENCODED_LEN],
+    len: usize,
+}
+
+impl EncodedInt {
+    /// Get encoded bytes.
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buf[..self.len]
+    }
+}
+
+impl AsRef<[u8]> for EncodedInt {
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+/// Encode `x` as its big-endian representation prefixed with its length
+/// in bytes (the SP 800-185 `left_encode` function).
+#[must_use]
+pub fn left_encode(x: u64) -> EncodedInt {
+    let (n, bytes) = int_bytes(x);
+    let n_len = usize::from(n);
+    let mut buf = [0u8; MAX_ENCODED_LEN];
+    buf[0] = n;
+    buf[1..=n_len].copy_from_slice(&bytes[bytes.len() - n_len..]);
+    EncodedInt {
+        buf,
+        len: n_len + 1,
+    }
+}
+
+/// Encode `x` as its big-endian representation suffixed with its length
+/// in bytes (the SP 800-185 `right_encode` function).
+#[must_use]
+pub fn right_encode(x: u64) -> EncodedInt {
+    let (n, bytes) = int_bytes(x);
+    let n_len = usize::from(n);
+    let mut buf = [0u8; MAX_ENCODED_LEN];
+    buf[..n_len].copy_from_slice(&bytes[bytes.len() - n_len..]);
+    buf[n_len] = n;
+    EncodedInt {
+        buf,
+        len: n_len + 1,
+    }
+}
+
+/// Feed `data` prefixed with `left_encode` of its length in bits into `hasher`
+/// (the SP 800-185 `encode_string` function).
+///
+/// # Panics
+/// If bit length of `data` does not fit into `u64`.
+pub fn encode_string<U: Update + ?Sized>(hasher: &mut U, data: &[u8]) {
+    hasher.update(left_encode(bit_len(data.len())).as_bytes());
+    hasher.update(data);
+}
+
+/// Feed `left_encode(w)` followed by concatenation of `parts` into `hasher` and pad
+/// the result with zeros to a multiple of `w` bytes (the SP 800-185 `bytepad` function).
+///
+/// # Panics
+/// If `w` is equal to zero or does not fit into `u64`.
+pub fn bytepad<U: Update + ?Sized>(hasher: &mut U, w: usize, parts: &[&[u8]]) {
+    assert!(w != 0, "bytepad width must not be zero");
+    let prefix = left_encode(u64::try_from(w).expect("bytepad width is too big"));
+    hasher.update(prefix.as_bytes());
+    let mut len = prefix.as_bytes().len() % w;
+    for part in parts {
+        hasher.update(part);
+        len = (len + part.len() % w) % w;
+    }
+
+    let zeros = [0u8; 64];
+    let mut pad_len = (w - len) % w;
+    while pad_len != 0 {
+        let n = pad_len.min(zeros.len());
+        hasher.update(&zeros[..n]);
+        pad_len -= n;
+    }
+}
+
+/// Builder which feeds a tuple of fields into a hasher using the TupleHash encoding.
+///
+/// Every field is encoded using [`encode_string`] and every list is encoded as
+/// `left_encode` of the number of its elements followed by the encoded elements.
+/// The encoding is injective for tuples with the same structure, i.e. applications
+/// should not hash tuples with different sequences of fields and lists using
+/// the same hasher without additional domain separation.
+///
+/// A sequence of fields followed by [`finish`][Self::finish] fed into cSHAKE
+/// with the `"TupleHash"` function name produces TupleHash as defined in SP 800-185.
+/// Lists are an extension of this crate which is not defined by SP 800-185.
+///
+/// ```
+/// use digest::{Update, encoding::Tuple};
+/// # #[derive(Default)]
+/// # struct Hasher(Vec<u8>);
+/// # impl Update for Hasher {
+/// #     fn update(&mut self, data: &[u8]) { self.0.extend_from_slice(data) }
+/// # }
+///
+/// let a = Tuple::new(Hasher::default()).field(b"ab").field(b"c").into_inner();
+/// let b = Tuple::new(Hasher::default()).field(b"a").field(b"bc").into_inner();
+/// assert_ne!(a.0, b.0);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Tuple<U: Update> {
+    hasher: U,
+}
+
+impl<U: Update> Tuple<U> {
+    /// Create new tuple builder which feeds data into `hasher`.
+    pub fn new(hasher: U) -> Self {
+        Self { hasher }
+    }
+
+    /// Feed field into the hasher.
+    pub fn update_field(&mut self, data: impl AsRef<[u8]>) {
+        encode_string(&mut self.hasher, data.as_ref());
+    }
+
+    /// Feed field into the hasher in a chained manner.
+    #[must_use]
+    pub fn field(mut self, data: impl AsRef<[u8]>) -> Self {
+        self.update_field(data);
+        self
+    }
+
+    /// Feed list of fields into the hasher.
+    ///
+    /// # Panics
+    /// If the number of list elements or bit length of an element does not fit into `u64`.
+    pub fn update_list<I>(&mut self, items: I)
+    where
+        I: IntoIterator,
+        I::IntoIter: ExactSizeIterator,
+        I::Item: AsRef<[u8]>,
+    {
+        let items = items.into_iter();
+        let count = u64::try_from(items.len()).expect("list is too long");
+        self.hasher.update(left_encode(count).as_bytes());
+        for item in items {
+            self.update_field(item);
+        }
+    }
+
+    /// Feed list of fields into the hasher in a chained manner.
+    ///
+    /// # Panics
+    /// If the number of list elements or bit length of an element does not fit into `u64`.
+    #[must_use]
+    pub fn list<I>(mut self, items: I) -> Self
+    where
+        I: IntoIterator,
+        I::IntoIter: ExactSizeIterator,
+        I::Item: AsRef<[u8]>,
+    {
+        self.update_list(items);
+        self
+    }
+
+    /// Get mutable reference to the wrapped hasher.
+    ///
+    /// Data fed directly into the hasher is not encoded and can break
+    /// injectivity of the tuple encoding.
+    pub fn get_mut(&mut self) -> &mut U {
+        &mut self.hasher
+    }
+
+    /// Unwrap the hasher.
+    pub fn into_inner(self) -> U {
+        self.hasher
+    }
+
+    /// Feed `right_encode(output_bits)` into the hasher and unwrap it.
+    ///
+    /// This finishes the TupleHash input encoding, which binds the requested
+    /// output length (in bits) of an extendable-output function into the result.
+    pub fn finish(mut self, output_bits: u64) -> U {
+        self.hasher.update(right_encode(output_bits).as_bytes());
+        self.hasher
+    }
+}
+
+/// Get minimal number of bytes needed to represent `x` (at least one)
+/// and big-endian representation of `x`.
+#[allow(clippy::cast_possible_truncation)]
+fn int_bytes(x: u64) -> (u8, [u8; 8]) {
+    let n = (u64::BITS - x.leading_zeros()).div_ceil(8).max(1);
+    // `n` is never bigger than 8, so the cast is lossless
+    (n as u8, x.to_be_bytes())
+}
+
+/// Convert byte length into bit length.
+fn bit_len(len: usize) -> u64 {
+    u64::try_from(len)
+        .ok()
+        .and_then(|len| len.checked_mul(8))
+        .expect("data is too long")
+}
@@ -0,0 +1,122 @@
+use crate::Update;
+use std::io;
+
+/// Size of the stack buffer used for reading data.
+const READ_BUF_LEN: usize = 8 * 1024;
+
+/// Wrapper around an [`Update`] implementation which implements [`io::Write`].
+///
+/// All data written into the wrapper is passed to the wrapped hasher or MAC.
+#[derive(Clone, Debug, Default)]
+pub struct HashWriter<D> {
+    hasher: D,
+}
+
+impl<D: Update> HashWriter<D> {
+    /// Wrap the hasher.
+    pub fn new(hasher: D) -> Self {
+        Self { hasher }
+    }
+
+    /// Get reference to the wrapped hasher.
+    pub fn get_ref(&self) -> &D {
+        &self.hasher
+    }
+
+    /// Get mutable reference to the wrapped hasher.
+    pub fn get_mut(&mut self) -> &mut D {
+        &mut self.hasher
+    }
+
+    /// Unwrap the hasher.
+    pub fn into_inner(self) -> D {
+        self.hasher
+    }
+}
+
+impl<D: Update> io::Write for HashWriter<D> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.hasher.update(buf);
+        Ok(buf.len())
+    }
+
+    #[inline]
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        self.hasher.update(buf);
+        Ok(())
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Wrapper around an [`io::Read`] implementation which passes all read data
+/// to the wrapped hasher or MAC.
+#[derive(Clone, Debug, Default)]
+pub struct HashReader<R, D> {
+    reader: R,
+    hasher: D,
+}
+
+impl<R: io::Read, D: Update> HashReader<R, D> {
+    /// Wrap the reader and the hasher.
+    pub fn new(reader: R, hasher: D) -> Self {
+        Self { reader, hasher }
+    }
+
+    /// Get reference to the wrapped reader.
+    pub fn get_reader(&self) -> &R {
+        &self.reader
+    }
+
+    /// Get reference to the wrapped hasher.
+    pub fn get_hasher(&self) -> &D {
+        &self.hasher
+    }
+
+    /// Get mutable reference to the wrapped hasher.
+    pub fn get_hasher_mut(&mut self) -> &mut D {
+        &mut self.hasher
+    }
+
+    /// Unwrap the reader and the hasher.
+    pub fn into_inner(self) -> (R, D) {
+        (self.reader, self.hasher)
+    }
+
+    /// Unwrap the hasher dropping the reader.
+    pub fn into_hasher(self) -> D {
+        self.hasher
+    }
+}
+
+impl<R: io::Read, D: Update> io::Read for HashReader<R, D> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.reader.read(buf)?;
+        self.hasher.update(&buf[..n]);
+        Ok(n)
+    }
+}
+
+/// Read data from `reader` until EOF and pass it in chunks to `f`.
+///
+/// Returns number of processed bytes.
+pub(crate) fn read_chunks(mut reader: impl io::Read, mut f: impl FnMut(&[u8])) -> io::Result<u64> {
+    let mut buf = [0u8; READ_BUF_LEN];
+    let mut total = 0u64;
+    loop {
+        match reader.read(&mut buf) {
+            Ok(0) => return Ok(total),
+            Ok(n) => {
+                f(&buf[..n]);
+                total += n as u64;
+            }
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+}
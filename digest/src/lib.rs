@@ -12,11 +12,12 @@
 //!
 //! Traits in this crate are organized into the following levels:
 //!
-//! - **High-level convenience traits**: [`Digest`], [`DynDigest`], [`Mac`].
+//! - **High-level convenience traits**: [`Digest`], [`DynDigest`], [`DynXof`], [`Mac`].
 //!   Wrappers around lower-level traits for most common use-cases. Users should
 //!   usually prefer using these traits.
 //! - **Mid-level traits**: [`Update`], [`FixedOutput`], [`FixedOutputReset`], [`ExtendableOutput`],
-//!   [`ExtendableOutputReset`], [`XofReader`], [`Reset`], [`KeyInit`], and [`InnerInit`].
+//!   [`ExtendableOutputReset`], [`XofReader`], [`XofReaderSeek`], [`Reset`], [`KeyInit`],
+//!   and [`InnerInit`].
 //!   These traits atomically describe available functionality of an algorithm.
 //! - **Marker traits**: [`HashMarker`], [`MacMarker`]. Used to distinguish
 //!   different algorithm classes.
@@ -28,18 +29,22 @@
 //! Additionally hash functions implement traits from the standard library:
 //! [`Default`] and [`Clone`].
 //!
-//! This crate does not provide any implementations of the `io::Read/Write` traits,
-//! see the [`digest-io`] crate for `std::io`-compatibility wrappers.
-//!
-//! [`digest-io`]: https://docs.rs/digest-io
+//! With enabled `std` feature this crate also provides `std::io` compatibility wrappers:
+//! `HashWriter` which implements `io::Write` for any [`Update`] implementation and
+//! `HashReader` which hashes data as it passes through an `io::Read` implementation.
+//! Additionally, the [`Digest`] trait gets the `update_reader` and `digest_reader` methods.
 
 #[cfg(feature = "alloc")]
 #[macro_use]
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
 #[cfg(feature = "rand_core")]
 pub use common::rand_core;
 
+#[cfg(feature = "serde")]
+pub use serde;
 #[cfg(feature = "zeroize")]
 pub use zeroize;
 
@@ -52,9 +57,24 @@ pub mod dev;
 #[cfg(feature = "block-api")]
 pub mod block_api;
 mod buffer_macros;
+#[cfg(feature = "alloc")]
+pub mod checkpoint;
 mod digest;
+pub mod encoding;
+#[cfg(feature = "std")]
+mod io;
 #[cfg(feature = "mac")]
 mod mac;
+#[cfg(feature = "block-api")]
+pub mod multi;
+#[cfg(feature = "alloc")]
+pub mod registry;
+#[cfg(feature = "serde")]
+pub mod serde_state;
+#[cfg(feature = "alloc")]
+pub mod tree;
+#[cfg(feature = "block-api")]
+pub mod truncated;
 mod xof_fixed;
 
 #[cfg(feature = "block-api")]
@@ -65,12 +85,16 @@ pub use const_oid;
 
 #[cfg(feature = "oid")]
 pub use crate::digest::DynDigestWithOid;
-pub use crate::digest::{Digest, DynDigest, HashMarker};
+pub use crate::digest::{Digest, DynDigest, DynXof, HashMarker};
+#[cfg(feature = "std")]
+pub use crate::io::{HashReader, HashWriter};
 #[cfg(feature = "mac")]
 pub use common::{InnerInit, InvalidLength, Key, KeyInit};
 pub use common::{Output, OutputSizeUser, Reset, array, typenum, typenum::consts};
 #[cfg(feature = "mac")]
 pub use mac::{CtOutput, Mac, MacError, MacMarker};
+#[cfg(feature = "block-api")]
+pub use truncated::Truncated;
 pub use xof_fixed::XofFixedWrapper;
 
 use common::typenum::Unsigned;
@@ -140,6 +164,29 @@ pub trait XofReader {
     }
 }
 
+/// Trait for XOF readers which support random access to the output stream.
+pub trait XofReaderSeek: XofReader {
+    /// Get current position in the output stream in bytes, i.e. number
+    /// of bytes which were already read or skipped.
+    fn current_pos(&self) -> u64;
+
+    /// Try to move reader to the byte position `pos` in the output stream.
+    ///
+    /// # Errors
+    /// If the reader does not support seeking to `pos`, e.g. if it can
+    /// only skip forward and `pos` is smaller than the current position.
+    fn try_seek(&mut self, pos: u64) -> Result<(), XofSeekError>;
+
+    /// Move reader to the byte position `pos` in the output stream.
+    ///
+    /// # Panics
+    /// If the reader does not support seeking to `pos`.
+    fn seek(&mut self, pos: u64) {
+        self.try_seek(pos)
+            .expect("XOF reader does not support seeking to the position");
+    }
+}
+
 /// Trait for hash functions with extendable-output (XOF).
 pub trait ExtendableOutput: Sized + Update {
     /// Reader
@@ -236,6 +283,16 @@ pub trait CollisionResistance {
     type CollisionResistance: Unsigned;
 }
 
+/// Types which identify the format of their serialized state.
+///
+/// Hashers generated by the buffer macros of this crate use name and version
+/// of the crate which invokes the macro, i.e. state serialized by one release
+/// of a hash crate is considered incompatible with all other releases.
+pub trait StateVersion: common::hazmat::SerializableState {
+    /// Identifier of the [`SerializableState`][common::hazmat::SerializableState] format.
+    const STATE_VERSION: &'static str;
+}
+
 /// The error type used in variable hash traits.
 #[derive(Clone, Copy, Debug, Default)]
 pub struct InvalidOutputSize;
@@ -259,3 +316,15 @@ impl fmt::Display for InvalidBufferSize {
 }
 
 impl core::error::Error for InvalidBufferSize {}
+
+/// The error type returned when an XOF reader can not seek to the requested position.
+#[derive(Default, Debug, Copy, Clone, Eq, PartialEq)]
+pub struct XofSeekError;
+
+impl fmt::Display for XofSeekError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("XOF reader can not seek to the requested position")
+    }
+}
+
+impl core::error::Error for XofSeekError {}
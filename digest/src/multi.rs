@@ -0,0 +1,218 @@
+//! Multi-buffer hashing of independent messages.
+
+use crate::block_api::{
+    Block, BlockSizeUser, Buffer, EagerHash, FixedOutputCore, ParBlocks, ParBlocksSizeUser,
+    ParUpdateCore, UpdateCore,
+};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use common::{Output, array::Array, typenum::Unsigned};
+use core::fmt;
+
+/// Number of lanes used by [`MultiDigest<D>`].
+pub type Lanes<D> = <<D as EagerHash>::Core as ParBlocksSizeUser>::ParBlocksSize;
+
+/// Lane outputs of [`MultiDigest<D>`].
+pub type MultiOutput<D> = Array<Output<<D as EagerHash>::Core>, Lanes<D>>;
+
+/// Hasher which computes hashes of several independent messages at once.
+///
+/// Every lane has a separate hash state and buffer. Whole blocks available
+/// in all lanes are processed together using [`ParUpdateCore::update_par_blocks`],
+/// which allows hash cores to use multi-buffer backends. The rest of data is
+/// processed lane by lane.
+pub struct MultiDigest<D>
+where
+    D: EagerHash,
+    D::Core: ParUpdateCore,
+{
+    cores: Array<D::Core, Lanes<D>>,
+    buffers: Array<Buffer<D::Core>, Lanes<D>>,
+}
+
+impl<D> MultiDigest<D>
+where
+    D: EagerHash,
+    D::Core: ParUpdateCore,
+{
+    /// Create new hasher instance.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Update every lane using the corresponding message part in `data`.
+    ///
+    /// Only blocks which are available in all lanes are processed in parallel,
+    /// so an empty or short message part in one lane limits parallel processing
+    /// of the other lanes to the length of that part. The remaining blocks
+    /// are processed lane by lane. For messages of different lengths prefer
+    /// [`digest_many`][Self::digest_many], which starts hashing the next message
+    /// in a lane as soon as the previous one is finished.
+    pub fn update(&mut self, data: &Array<&[u8], Lanes<D>>) {
+        let block_size = <D::Core as BlockSizeUser>::BlockSize::USIZE;
+        let Self { cores, buffers } = self;
+
+        // Complete partially filled lane buffers
+        let mut rest = data.clone();
+        for ((core, buffer), rest) in cores.iter_mut().zip(buffers.iter_mut()).zip(&mut rest) {
+            let pos = buffer.get_pos();
+            if pos != 0 {
+                let (head, tail) = rest.split_at(rest.len().min(block_size - pos));
+                buffer.digest_blocks(head, |blocks| core.update_blocks(blocks));
+                *rest = tail;
+            }
+        }
+
+        // Process blocks available in all lanes in parallel
+        let blocks: Array<&[Block<D::Core>], Lanes<D>> =
+            rest.clone().map(|lane| Array::slice_as_chunks(lane).0);
+        let par_len = blocks.iter().map(|lane| lane.len()).min().unwrap_or(0);
+        for i in 0..par_len {
+            let par_blocks = ParBlocks::<D::Core>::from_fn(|lane| blocks[lane][i].clone());
+            D::Core::update_par_blocks(cores, &par_blocks);
+        }
+
+        // Process the remaining data lane by lane
+        for ((core, buffer), rest) in cores.iter_mut().zip(buffers.iter_mut()).zip(rest) {
+            let tail = &rest[par_len * block_size..];
+            buffer.digest_blocks(tail, |blocks| core.update_blocks(blocks));
+        }
+    }
+
+    /// Retrieve results of all lanes and consume the hasher.
+    #[must_use]
+    pub fn finalize(mut self) -> MultiOutput<D> {
+        self.finalize_lanes()
+    }
+
+    /// Retrieve results of all lanes and reset the hasher.
+    pub fn finalize_reset(&mut self) -> MultiOutput<D> {
+        let res = self.finalize_lanes();
+        *self = Self::default();
+        res
+    }
+
+    /// Compute hashes of `msgs`.
+    ///
+    /// Every lane takes the next message from `msgs` as soon as it finishes
+    /// hashing the previous one, so messages of different lengths are processed
+    /// in parallel until there are no more messages left to distribute
+    /// between lanes. The results are returned in the same order as `msgs`.
+    #[cfg(feature = "alloc")]
+    #[must_use]
+    pub fn digest_many(msgs: &[&[u8]]) -> Vec<Output<D::Core>> {
+        let block_size = <D::Core as BlockSizeUser>::BlockSize::USIZE;
+        let mut res = Vec::with_capacity(msgs.len());
+        res.resize(msgs.len(), Output::<D::Core>::default());
+
+        let mut queue = msgs.iter().copied().enumerate();
+        let mut lanes: Array<Option<(usize, &[u8])>, Lanes<D>> = Array::from_fn(|_| queue.next());
+        let mut hasher = Self::default();
+        let Self { cores, buffers } = &mut hasher;
+
+        loop {
+            // Finish messages with less than one block left and refill their lanes
+            for ((core, buffer), lane) in cores.iter_mut().zip(buffers.iter_mut()).zip(&mut lanes) {
+                loop {
+                    match *lane {
+                        Some((idx, rest)) if rest.len() < block_size => {
+                            finalize_lane(core, buffer, rest, &mut res[idx]);
+                            *lane = queue.next();
+                        }
+                        _ => break,
+                    }
+                }
+            }
+
+            // Process blocks available in all lanes in parallel
+            let par_len = lanes
+                .iter()
+                .map(|lane| lane.map_or(0, |(_, rest)| rest.len() / block_size))
+                .min()
+                .unwrap_or(0);
+            if par_len == 0 {
+                break;
+            }
+            let blocks: Array<&[Block<D::Core>], Lanes<D>> = lanes
+                .clone()
+                .map(|lane| lane.map_or(&[][..], |(_, rest)| Array::slice_as_chunks(rest).0));
+            for i in 0..par_len {
+                let par_blocks = ParBlocks::<D::Core>::from_fn(|lane| blocks[lane][i].clone());
+                D::Core::update_par_blocks(cores, &par_blocks);
+            }
+            for (_, rest) in lanes.iter_mut().flatten() {
+                *rest = &rest[par_len * block_size..];
+            }
+        }
+
+        // The queue is exhausted, process the remaining messages lane by lane
+        for ((core, buffer), lane) in cores.iter_mut().zip(buffers.iter_mut()).zip(lanes) {
+            if let Some((idx, rest)) = lane {
+                finalize_lane(core, buffer, rest, &mut res[idx]);
+            }
+        }
+        res
+    }
+
+    fn finalize_lanes(&mut self) -> MultiOutput<D> {
+        let mut res = MultiOutput::<D>::default();
+        let Self { cores, buffers } = self;
+        for ((core, buffer), out) in cores.iter_mut().zip(buffers.iter_mut()).zip(&mut res) {
+            core.finalize_fixed_core(buffer, out);
+        }
+        res
+    }
+}
+
+/// Hash the remaining `data` of a lane, write the result into `out`, and reset the lane.
+#[cfg(feature = "alloc")]
+fn finalize_lane<C: FixedOutputCore + Default>(
+    core: &mut C,
+    buffer: &mut Buffer<C>,
+    data: &[u8],
+    out: &mut Output<C>,
+) {
+    buffer.digest_blocks(data, |blocks| core.update_blocks(blocks));
+    core.finalize_fixed_core(buffer, out);
+    *core = C::default();
+    buffer.reset();
+}
+
+impl<D> Default for MultiDigest<D>
+where
+    D: EagerHash,
+    D::Core: ParUpdateCore,
+{
+    #[inline]
+    fn default() -> Self {
+        Self {
+            cores: Default::default(),
+            buffers: Default::default(),
+        }
+    }
+}
+
+impl<D> Clone for MultiDigest<D>
+where
+    D: EagerHash,
+    D::Core: ParUpdateCore,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            cores: self.cores.clone(),
+            buffers: self.buffers.clone(),
+        }
+    }
+}
+
+impl<D> fmt::Debug for MultiDigest<D>
+where
+    D: EagerHash,
+    D::Core: ParUpdateCore,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("MultiDigest { ... }")
+    }
+}
@@ -0,0 +1,158 @@
+//! Runtime registry of hash functions.
+//!
+//! [`Registry`] maps algorithm names and (with enabled `oid` feature) object
+//! identifiers to constructors of boxed [`DynDigest`] instances. It allows to
+//! select a hash function based on data known only at runtime, e.g. on an OID
+//! stored in an X.509 certificate or on a name provided in a configuration file.
+
+use crate::{DynDigest, checkpoint::alg_name};
+use alloc::{boxed::Box, string::String, vec::Vec};
+use common::AlgorithmName;
+#[cfg(feature = "oid")]
+use const_oid::{AssociatedOid, ObjectIdentifier};
+use core::fmt;
+
+/// Constructor of boxed hasher instances.
+pub type Constructor = fn() -> Box<dyn DynDigest>;
+
+/// Registry entry.
+#[derive(Clone)]
+struct Entry {
+    name: String,
+    #[cfg(feature = "oid")]
+    oid: Option<ObjectIdentifier>,
+    ctor: Constructor,
+}
+
+/// Registry of hash function constructors.
+///
+/// Names are matched ignoring ASCII case. Registering a constructor with an already
+/// registered name replaces the previous constructor. OID of the previous entry is kept
+/// if the new registration does not provide an OID.
+#[derive(Clone, Default)]
+pub struct Registry {
+    entries: Vec<Entry>,
+}
+
+impl Registry {
+    /// Create an empty registry.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register hash function `D` using name returned by its [`AlgorithmName`] implementation.
+    pub fn register<D>(&mut self)
+    where
+        D: DynDigest + AlgorithmName + Default + 'static,
+    {
+        self.insert(Entry {
+            name: alg_name::<D>(),
+            #[cfg(feature = "oid")]
+            oid: None,
+            ctor: new_boxed::<D>,
+        });
+    }
+
+    /// Register hash function `D` using its algorithm name and OID.
+    #[cfg(feature = "oid")]
+    pub fn register_with_oid<D>(&mut self)
+    where
+        D: DynDigest + AlgorithmName + AssociatedOid + Default + 'static,
+    {
+        self.insert(Entry {
+            name: alg_name::<D>(),
+            oid: Some(D::OID),
+            ctor: new_boxed::<D>,
+        });
+    }
+
+    /// Register constructor `ctor` under `name`.
+    ///
+    /// This method can be used to register aliases, e.g. `"SHA-256"` in addition
+    /// to the algorithm name provided by the hash function.
+    pub fn register_constructor(&mut self, name: &str, ctor: Constructor) {
+        self.insert(Entry {
+            name: name.into(),
+            #[cfg(feature = "oid")]
+            oid: None,
+            ctor,
+        });
+    }
+
+    /// Register constructor `ctor` under `name` and `oid`.
+    #[cfg(feature = "oid")]
+    pub fn register_constructor_with_oid(
+        &mut self,
+        name: &str,
+        oid: ObjectIdentifier,
+        ctor: Constructor,
+    ) {
+        self.insert(Entry {
+            name: name.into(),
+            oid: Some(oid),
+            ctor,
+        });
+    }
+
+    /// Create hasher instance registered under `name`.
+    #[must_use]
+    pub fn get_by_name(&self, name: &str) -> Option<Box<dyn DynDigest>> {
+        self.find_by_name(name).map(|entry| (entry.ctor)())
+    }
+
+    /// Create hasher instance registered under `oid`.
+    #[cfg(feature = "oid")]
+    #[must_use]
+    pub fn get_by_oid(&self, oid: &ObjectIdentifier) -> Option<Box<dyn DynDigest>> {
+        self.entries
+            .iter()
+            .find(|entry| entry.oid.as_ref() == Some(oid))
+            .map(|entry| (entry.ctor)())
+    }
+
+    /// Check whether a hash function is registered under `name`.
+    #[must_use]
+    pub fn contains_name(&self, name: &str) -> bool {
+        self.find_by_name(name).is_some()
+    }
+
+    /// Iterate over registered names.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(|entry| entry.name.as_str())
+    }
+
+    fn find_by_name(&self, name: &str) -> Option<&Entry> {
+        self.entries
+            .iter()
+            .find(|entry| entry.name.eq_ignore_ascii_case(name))
+    }
+
+    fn insert(&mut self, entry: Entry) {
+        match self
+            .entries
+            .iter_mut()
+            .find(|e| e.name.eq_ignore_ascii_case(&entry.name))
+        {
+            Some(e) => {
+                #[cfg(feature = "oid")]
+                let entry = Entry {
+                    oid: entry.oid.or(e.oid),
+                    ..entry
+                };
+                *e = entry;
+            }
+            None => self.entries.push(entry),
+        }
+    }
+}
+
+impl fmt::Debug for Registry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.names()).finish()
+    }
+}
+
+fn new_boxed<D: DynDigest + Default + 'static>() -> Box<dyn DynDigest> {
+    Box::new(D::default())
+}
@@ -0,0 +1,151 @@
+//! Serde support for hasher states.
+//!
+//! Hasher types which implement [`SerializableState`] can be serialized using
+//! the functions from this module. In human-readable formats (e.g. JSON) the state
+//! is encoded as a lowercase hex string, while binary formats (e.g. CBOR) store
+//! it as a byte string.
+//!
+//! Hashers generated by the buffer macros of this crate implement `Serialize` and
+//! `Deserialize` using these functions when the `serde` feature is enabled. For other
+//! types the functions can be used with the `with` field attribute:
+//!
+//! ```ignore
+//! #[derive(serde::Serialize, serde::Deserialize)]
+//! struct Task {
+//!     offset: u64,
+//!     #[serde(with = "digest::serde_state")]
+//!     hasher: MyHasher,
+//! }
+//! ```
+//!
+//! The `#[cfg(feature = "serde")]` attributes inside the buffer macros are evaluated
+//! in the crate which invokes the macro, not in `digest`. Hash crates which want
+//! to provide `serde` support for their hashers thus have to define their own
+//! `serde` feature which enables the `digest` one:
+//!
+//! ```toml
+//! [features]
+//! serde = ["digest/serde"]
+//! ```
+//!
+//! Without it the `Serialize` and `Deserialize` implementations are silently omitted,
+//! even if the `serde` feature of `digest` is enabled by another crate.
+//!
+//! Note that the serialized state contains data which was already absorbed
+//! by the hasher, so it should be handled as a secret if the hashed data is secret.
+
+use common::hazmat::{SerializableState, SerializedState};
+use common::typenum::Unsigned;
+use core::{fmt, marker::PhantomData};
+use serde::{
+    Deserializer, Serializer,
+    de::{self, Visitor},
+};
+
+/// Serialize state of `state` using the `serializer`.
+///
+/// # Errors
+/// Returns serializer errors.
+pub fn serialize<T, S>(state: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: SerializableState,
+    S: Serializer,
+{
+    let serialized = state.serialize();
+    if serializer.is_human_readable() {
+        serializer.collect_str(&HexDisplay(&serialized))
+    } else {
+        serializer.serialize_bytes(&serialized)
+    }
+}
+
+/// Deserialize state using the `deserializer`.
+///
+/// # Errors
+/// Returns deserializer errors, including errors for states with invalid length,
+/// invalid hex encoding, or contents rejected by [`SerializableState::deserialize`].
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: SerializableState,
+    D: Deserializer<'de>,
+{
+    let visitor = StateVisitor(PhantomData);
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_str(visitor)
+    } else {
+        deserializer.deserialize_bytes(visitor)
+    }
+}
+
+/// Lowercase hex formatting of a byte slice.
+struct HexDisplay<'a>(&'a [u8]);
+
+impl fmt::Display for HexDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.iter().try_for_each(|b| write!(f, "{b:02x}"))
+    }
+}
+
+struct StateVisitor<T>(PhantomData<T>);
+
+impl<T: SerializableState> StateVisitor<T> {
+    fn finish<E: de::Error>(state: &SerializedState<T>) -> Result<T, E> {
+        T::deserialize(state).map_err(E::custom)
+    }
+}
+
+impl<'de, T: SerializableState> Visitor<'de> for StateVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let len = T::SerializedStateSize::USIZE;
+        write!(
+            f,
+            "hasher state of {len} bytes or hex string of {len} bytes"
+        )
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<T, E> {
+        let v = v.as_bytes();
+        let mut state = SerializedState::<T>::default();
+        if v.len() != 2 * state.len() {
+            return Err(E::invalid_length(v.len() / 2, &self));
+        }
+        for (dst, src) in state.iter_mut().zip(v.chunks_exact(2)) {
+            let (Some(hi), Some(lo)) = (hex_val(src[0]), hex_val(src[1])) else {
+                return Err(E::custom("invalid hex string"));
+            };
+            *dst = (hi << 4) | lo;
+        }
+        Self::finish(&state)
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<T, E> {
+        let state =
+            SerializedState::<T>::try_from(v).map_err(|_| E::invalid_length(v.len(), &self))?;
+        Self::finish(&state)
+    }
+
+    fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<T, A::Error> {
+        let mut state = SerializedState::<T>::default();
+        for (i, dst) in state.iter_mut().enumerate() {
+            *dst = seq
+                .next_element()?
+                .ok_or_else(|| de::Error::invalid_length(i, &self))?;
+        }
+        if seq.next_element::<u8>()?.is_some() {
+            return Err(de::Error::invalid_length(state.len() + 1, &self));
+        }
+        Self::finish(&state)
+    }
+}
+
+/// Decode hex character (both lower and upper case are accepted).
+fn hex_val(c: u8) -> Option<u8> {
+    match c {
+        b'0'..=b'9' => Some(c - b'0'),
+        b'a'..=b'f' => Some(c - b'a' + 10),
+        b'A'..=b'F' => Some(c - b'A' + 10),
+        _ => None,
+    }
+}
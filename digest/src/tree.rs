@@ -0,0 +1,477 @@
+//! Generic Merkle tree hashing mode.
+//!
+//! Message is split into leaves of [`TreeConfig::leaf_size`] bytes (the last leaf may be
+//! shorter, empty message results in one empty leaf). Leaf hashes are computed as
+//! `H(leaf_prefix || leaf)`. Then on each level consecutive hashes are split into groups
+//! of [`TreeConfig::fan_out`] elements and each group is hashed as
+//! `H(node_prefix || child_1 || ... || child_k)`. If the last group on a level contains
+//! only one element, it gets promoted to the next level without hashing.
+//! The process is repeated until only the root hash remains.
+//!
+//! Distinct leaf and node prefixes provide domain separation between leaves and
+//! interior nodes, which prevents second preimage attacks on the tree structure.
+//! By default the single byte prefixes `0x00` and `0x01` are used, same as in [RFC 6962].
+//!
+//! [RFC 6962]: https://www.rfc-editor.org/rfc/rfc6962#section-2.1
+
+use crate::{Digest, Output};
+use alloc::vec::Vec;
+use core::fmt;
+
+/// Configuration of the tree hashing mode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TreeConfig {
+    leaf_size: usize,
+    fan_out: usize,
+    leaf_prefix: &'static [u8],
+    node_prefix: &'static [u8],
+}
+
+impl Default for TreeConfig {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            leaf_size: 1024,
+            fan_out: 2,
+            leaf_prefix: &[0x00],
+            node_prefix: &[0x01],
+        }
+    }
+}
+
+impl TreeConfig {
+    /// Create configuration with the given leaf size in bytes and fan-out
+    /// using the default prefixes.
+    ///
+    /// # Errors
+    /// If `leaf_size` is equal to zero or `fan_out` is smaller than 2.
+    pub fn new(leaf_size: usize, fan_out: usize) -> Result<Self, InvalidTreeConfig> {
+        if leaf_size == 0 || fan_out < 2 {
+            return Err(InvalidTreeConfig);
+        }
+        Ok(Self {
+            leaf_size,
+            fan_out,
+            ..Default::default()
+        })
+    }
+
+    /// Replace prefixes used for hashing of leaves and interior nodes.
+    ///
+    /// # Errors
+    /// If one of the prefixes is a prefix of the other one (including the case of
+    /// empty or equal prefixes).
+    pub fn with_prefixes(
+        self,
+        leaf_prefix: &'static [u8],
+        node_prefix: &'static [u8],
+    ) -> Result<Self, InvalidTreeConfig> {
+        if leaf_prefix.starts_with(node_prefix) || node_prefix.starts_with(leaf_prefix) {
+            return Err(InvalidTreeConfig);
+        }
+        Ok(Self {
+            leaf_prefix,
+            node_prefix,
+            ..self
+        })
+    }
+
+    /// Size of leaves in bytes.
+    #[must_use]
+    pub fn leaf_size(&self) -> usize {
+        self.leaf_size
+    }
+
+    /// Maximum number of children of interior nodes.
+    #[must_use]
+    pub fn fan_out(&self) -> usize {
+        self.fan_out
+    }
+
+    /// Prefix used for hashing of leaves.
+    #[must_use]
+    pub fn leaf_prefix(&self) -> &'static [u8] {
+        self.leaf_prefix
+    }
+
+    /// Prefix used for hashing of interior nodes.
+    #[must_use]
+    pub fn node_prefix(&self) -> &'static [u8] {
+        self.node_prefix
+    }
+
+    /// Compute hash of a single leaf.
+    #[must_use]
+    pub fn hash_leaf<D: Digest>(&self, leaf: &[u8]) -> Output<D> {
+        D::new_with_prefix(self.leaf_prefix)
+            .chain_update(leaf)
+            .finalize()
+    }
+
+    /// Compute hash of an interior node from hashes of its children.
+    ///
+    /// A single child hash is returned as-is.
+    #[must_use]
+    pub fn hash_node<D: Digest>(&self, children: &[Output<D>]) -> Output<D> {
+        match children {
+            [child] => child.clone(),
+            _ => {
+                let mut hasher = D::new_with_prefix(self.node_prefix);
+                for child in children {
+                    hasher.update(child);
+                }
+                hasher.finalize()
+            }
+        }
+    }
+
+    /// Number of leaves for message with length `len`.
+    fn leaf_count(&self, len: usize) -> usize {
+        len.div_ceil(self.leaf_size).max(1)
+    }
+}
+
+/// Merkle tree computed over a message.
+///
+/// The tree stores hashes of all nodes, so it can be used to produce
+/// inclusion proofs for any leaf.
+pub struct MerkleTree<D: Digest> {
+    config: TreeConfig,
+    /// Hashes of nodes on each level, starting from leaves and ending with the root
+    levels: Vec<Vec<Output<D>>>,
+}
+
+impl<D: Digest + Clone> MerkleTree<D> {
+    /// Compute tree over `data`.
+    #[must_use]
+    pub fn new(config: TreeConfig, data: &[u8]) -> Self {
+        let leaf_hasher = D::new_with_prefix(config.leaf_prefix);
+        let leaves = match data {
+            [] => vec![leaf_hasher.finalize()],
+            _ => data
+                .chunks(config.leaf_size)
+                .map(|leaf| leaf_hasher.clone().chain_update(leaf).finalize())
+                .collect(),
+        };
+        Self::from_leaf_hashes(config, leaves)
+    }
+
+    /// Compute tree from precomputed leaf hashes.
+    ///
+    /// # Panics
+    /// If `leaves` is empty.
+    #[must_use]
+    pub fn from_leaf_hashes(config: TreeConfig, leaves: Vec<Output<D>>) -> Self {
+        assert!(!leaves.is_empty(), "tree must contain at least one leaf");
+        let mut levels = vec![leaves];
+        while let Some(level) = levels.last().filter(|level| level.len() > 1) {
+            let next = level
+                .chunks(config.fan_out)
+                .map(|children| config.hash_node::<D>(children))
+                .collect();
+            levels.push(next);
+        }
+        Self { config, levels }
+    }
+
+    /// Compute tree over `data` hashing leaves in parallel.
+    #[cfg(feature = "rayon")]
+    #[must_use]
+    pub fn par_new(config: TreeConfig, data: &[u8]) -> Self
+    where
+        D: Send + Sync,
+    {
+        use rayon::{iter::ParallelIterator, slice::ParallelSlice};
+
+        let leaf_hasher = D::new_with_prefix(config.leaf_prefix);
+        let leaves = match data {
+            [] => vec![leaf_hasher.finalize()],
+            _ => data
+                .par_chunks(config.leaf_size)
+                .map(|leaf| leaf_hasher.clone().chain_update(leaf).finalize())
+                .collect(),
+        };
+        Self::from_leaf_hashes(config, leaves)
+    }
+
+    /// Get the root hash.
+    #[must_use]
+    pub fn root(&self) -> &Output<D> {
+        &self.levels[self.levels.len() - 1][0]
+    }
+
+    /// Get configuration used by the tree.
+    #[must_use]
+    pub fn config(&self) -> &TreeConfig {
+        &self.config
+    }
+
+    /// Get hashes of leaves.
+    #[must_use]
+    pub fn leaf_hashes(&self) -> &[Output<D>] {
+        &self.levels[0]
+    }
+
+    /// Get inclusion proof for leaf with index `leaf_index`.
+    ///
+    /// Returns `None` if the tree does not contain leaf with the given index.
+    #[must_use]
+    pub fn proof(&self, leaf_index: usize) -> Option<InclusionProof<D>> {
+        let leaf_count = self.levels[0].len();
+        if leaf_index >= leaf_count {
+            return None;
+        }
+        let fan_out = self.config.fan_out;
+        let mut siblings = Vec::new();
+        let mut idx = leaf_index;
+        for level in &self.levels[..self.levels.len() - 1] {
+            let start = idx - idx % fan_out;
+            let group = &level[start..level.len().min(start + fan_out)];
+            if group.len() > 1 {
+                let pos = idx - start;
+                siblings.extend_from_slice(&group[..pos]);
+                siblings.extend_from_slice(&group[pos + 1..]);
+            }
+            idx /= fan_out;
+        }
+        Some(InclusionProof {
+            leaf_index,
+            leaf_count,
+            siblings,
+        })
+    }
+}
+
+impl<D: Digest + Clone> Clone for MerkleTree<D> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            config: self.config,
+            levels: self.levels.clone(),
+        }
+    }
+}
+
+impl<D: Digest> fmt::Debug for MerkleTree<D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MerkleTree")
+            .field("config", &self.config)
+            .field("levels", &self.levels)
+            .finish()
+    }
+}
+
+/// Proof of inclusion of a leaf into a [`MerkleTree`].
+///
+/// The proof contains hashes of all siblings of the nodes on the path from the leaf
+/// to the root, ordered from the bottom level to the top one and by position inside
+/// each group. Positions of the nodes are derived from the leaf index and the number
+/// of leaves in the tree.
+///
+/// Note that the root hash does not commit to the number of leaves, so the leaf index
+/// and the leaf count are not authenticated by a successful verification. For example,
+/// the last leaf of a binary tree with 3 leaves gets promoted to the second level
+/// without hashing, so its proof also verifies as a proof for the leaf with index 1
+/// in a tree with 2 leaves. Applications which rely on the leaf position or the tree size
+/// must authenticate them separately, e.g. by signing the tree size together with
+/// the root hash as done in [RFC 6962].
+///
+/// [RFC 6962]: https://www.rfc-editor.org/rfc/rfc6962#section-3.5
+pub struct InclusionProof<D: Digest> {
+    leaf_index: usize,
+    leaf_count: usize,
+    siblings: Vec<Output<D>>,
+}
+
+impl<D: Digest> InclusionProof<D> {
+    /// Create proof from its parts, e.g. after deserialization.
+    #[must_use]
+    pub fn new(leaf_index: usize, leaf_count: usize, siblings: Vec<Output<D>>) -> Self {
+        Self {
+            leaf_index,
+            leaf_count,
+            siblings,
+        }
+    }
+
+    /// Index of the proven leaf.
+    #[must_use]
+    pub fn leaf_index(&self) -> usize {
+        self.leaf_index
+    }
+
+    /// Number of leaves in the tree.
+    #[must_use]
+    pub fn leaf_count(&self) -> usize {
+        self.leaf_count
+    }
+
+    /// Hashes of the sibling nodes.
+    #[must_use]
+    pub fn siblings(&self) -> &[Output<D>] {
+        &self.siblings
+    }
+
+    /// Compute root hash from hash of the proven leaf.
+    ///
+    /// # Errors
+    /// If the leaf index is out of range or the number of sibling hashes does not
+    /// correspond to the tree structure.
+    pub fn compute_root(
+        &self,
+        config: &TreeConfig,
+        leaf_hash: &Output<D>,
+    ) -> Result<Output<D>, InvalidProof> {
+        if self.leaf_index >= self.leaf_count {
+            return Err(InvalidProof);
+        }
+        let fan_out = config.fan_out;
+        let mut siblings = self.siblings.iter();
+        let mut hash = leaf_hash.clone();
+        let mut idx = self.leaf_index;
+        let mut count = self.leaf_count;
+        while count > 1 {
+            let start = idx - idx % fan_out;
+            let group_len = count.min(start + fan_out) - start;
+            if group_len > 1 {
+                let pos = idx - start;
+                let mut hasher = D::new_with_prefix(config.node_prefix);
+                for i in 0..group_len {
+                    let child = if i == pos {
+                        &hash
+                    } else {
+                        siblings.next().ok_or(InvalidProof)?
+                    };
+                    hasher.update(child);
+                }
+                hash = hasher.finalize();
+            }
+            idx /= fan_out;
+            count = count.div_ceil(fan_out);
+        }
+        match siblings.next() {
+            Some(_) => Err(InvalidProof),
+            None => Ok(hash),
+        }
+    }
+
+    /// Verify the proof for the given leaf hash against the expected root hash.
+    ///
+    /// The leaf index and the leaf count are not authenticated by the root hash,
+    /// see the [`InclusionProof`] docs for more information.
+    ///
+    /// # Errors
+    /// If the proof is invalid.
+    pub fn verify_leaf_hash(
+        &self,
+        config: &TreeConfig,
+        leaf_hash: &Output<D>,
+        root: &Output<D>,
+    ) -> Result<(), InvalidProof> {
+        if self.compute_root(config, leaf_hash)? == *root {
+            Ok(())
+        } else {
+            Err(InvalidProof)
+        }
+    }
+
+    /// Verify the proof for the given leaf data against the expected root hash.
+    ///
+    /// The leaf index and the leaf count are not authenticated by the root hash,
+    /// see the [`InclusionProof`] docs for more information.
+    ///
+    /// # Errors
+    /// If the proof is invalid or `leaf` is longer than the configured leaf size.
+    pub fn verify(
+        &self,
+        config: &TreeConfig,
+        leaf: &[u8],
+        root: &Output<D>,
+    ) -> Result<(), InvalidProof> {
+        if leaf.len() > config.leaf_size {
+            return Err(InvalidProof);
+        }
+        self.verify_leaf_hash(config, &config.hash_leaf::<D>(leaf), root)
+    }
+}
+
+impl<D: Digest> Clone for InclusionProof<D> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            leaf_index: self.leaf_index,
+            leaf_count: self.leaf_count,
+            siblings: self.siblings.clone(),
+        }
+    }
+}
+
+impl<D: Digest> fmt::Debug for InclusionProof<D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InclusionProof")
+            .field("leaf_index", &self.leaf_index)
+            .field("leaf_count", &self.leaf_count)
+            .field("siblings", &self.siblings)
+            .finish()
+    }
+}
+
+/// Compute root hash of the tree over `data` without storing the whole tree.
+#[must_use]
+pub fn root<D: Digest + Clone>(config: &TreeConfig, data: &[u8]) -> Output<D> {
+    let leaf_hasher = D::new_with_prefix(config.leaf_prefix);
+    let mut stack: Vec<Vec<Output<D>>> = Vec::new();
+    let leaf_count = config.leaf_count(data.len());
+    let mut leaves = data.chunks(config.leaf_size);
+    for _ in 0..leaf_count {
+        let leaf = leaves.next().unwrap_or_default();
+        let mut hash = leaf_hasher.clone().chain_update(leaf).finalize();
+        // merge full groups on each level
+        for level in 0.. {
+            if stack.len() == level {
+                stack.push(Vec::with_capacity(config.fan_out));
+            }
+            stack[level].push(hash);
+            if stack[level].len() < config.fan_out {
+                break;
+            }
+            hash = config.hash_node::<D>(&stack[level]);
+            stack[level].clear();
+        }
+    }
+    // hash incomplete groups starting from the bottom level
+    let mut carry: Option<Output<D>> = None;
+    let top = stack.len() - 1;
+    for level in &mut stack[..top] {
+        level.extend(carry.take());
+        if !level.is_empty() {
+            carry = Some(config.hash_node::<D>(level));
+        }
+    }
+    stack[top].extend(carry);
+    config.hash_node::<D>(&stack[top])
+}
+
+/// Invalid tree configuration.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct InvalidTreeConfig;
+
+impl fmt::Display for InvalidTreeConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid tree hashing configuration")
+    }
+}
+
+impl core::error::Error for InvalidTreeConfig {}
+
+/// Inclusion proof verification failure.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct InvalidProof;
+
+impl fmt::Display for InvalidProof {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid inclusion proof")
+    }
+}
+
+impl core::error::Error for InvalidProof {}
@@ -0,0 +1,269 @@
+//! Truncation of fixed-output hash functions.
+
+use crate::{
+    CollisionResistance, FixedOutput, FixedOutputReset, HashMarker, Output, OutputSizeUser, Reset,
+    StateVersion, Update, block_api::TruncSide,
+};
+use common::{
+    AlgorithmName, BlockSizeUser,
+    array::ArraySize,
+    hazmat::{DeserializeStateError, SerializableState, SerializedState},
+    typenum::{IsLessOrEqual, Min, Minimum, Quot, True, U2, Unsigned},
+};
+use core::{fmt, marker::PhantomData, ops::Div};
+
+/// Types which define output side kept by [`Truncated`].
+pub trait TruncationSide {
+    /// Kept side of the output.
+    const SIDE: TruncSide;
+}
+
+/// Keep the left side of the hash output, i.e. its first bytes.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TruncLeft;
+
+impl TruncationSide for TruncLeft {
+    const SIDE: TruncSide = TruncSide::Left;
+}
+
+/// Keep the right side of the hash output, i.e. its last bytes.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TruncRight;
+
+impl TruncationSide for TruncRight {
+    const SIDE: TruncSide = TruncSide::Right;
+}
+
+/// Marker type used by [`Truncated`] without an associated OID.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoOid;
+
+/// Wrapper around [`FixedOutput`] hash `D` which truncates its output to `N` bytes.
+///
+/// Output side is selected by `S` ([`TruncLeft`] or [`TruncRight`]). With enabled
+/// `oid` feature, `Truncated` implements [`AssociatedOid`] using OID associated
+/// with `O`.
+///
+/// [`AssociatedOid`]: const_oid::AssociatedOid
+pub struct Truncated<D, N, S = TruncLeft, O = NoOid>
+where
+    D: FixedOutput,
+    N: ArraySize + IsLessOrEqual<D::OutputSize, Output = True>,
+    S: TruncationSide,
+{
+    hash: D,
+    _pd: PhantomData<(N, S, O)>,
+}
+
+impl<D, N, S, O> Truncated<D, N, S, O>
+where
+    D: FixedOutput,
+    N: ArraySize + IsLessOrEqual<D::OutputSize, Output = True>,
+    S: TruncationSide,
+{
+    /// Wrap hash function instance.
+    pub fn from_hash(hash: D) -> Self {
+        Self {
+            hash,
+            _pd: PhantomData,
+        }
+    }
+
+    /// Get reference to the wrapped hash function instance.
+    pub fn get_hash(&self) -> &D {
+        &self.hash
+    }
+
+    /// Unwrap the hash function instance.
+    pub fn into_hash(self) -> D {
+        self.hash
+    }
+
+    fn truncate(full_res: &Output<D>, out: &mut Output<Self>) {
+        let n = out.len();
+        let m = full_res.len() - n;
+        match S::SIDE {
+            TruncSide::Left => out.copy_from_slice(&full_res[..n]),
+            TruncSide::Right => out.copy_from_slice(&full_res[m..]),
+        }
+    }
+}
+
+impl<D, N, S, O> Clone for Truncated<D, N, S, O>
+where
+    D: FixedOutput + Clone,
+    N: ArraySize + IsLessOrEqual<D::OutputSize, Output = True>,
+    S: TruncationSide,
+{
+    fn clone(&self) -> Self {
+        Self::from_hash(self.hash.clone())
+    }
+}
+
+impl<D, N, S, O> Default for Truncated<D, N, S, O>
+where
+    D: FixedOutput + Default,
+    N: ArraySize + IsLessOrEqual<D::OutputSize, Output = True>,
+    S: TruncationSide,
+{
+    fn default() -> Self {
+        Self::from_hash(D::default())
+    }
+}
+
+impl<D, N, S, O> HashMarker for Truncated<D, N, S, O>
+where
+    D: FixedOutput + HashMarker,
+    N: ArraySize + IsLessOrEqual<D::OutputSize, Output = True>,
+    S: TruncationSide,
+{
+}
+
+impl<D, N, S, O> CollisionResistance for Truncated<D, N, S, O>
+where
+    D: FixedOutput + CollisionResistance,
+    N: ArraySize + IsLessOrEqual<D::OutputSize, Output = True> + Div<U2>,
+    S: TruncationSide,
+    D::CollisionResistance: Min<Quot<N, U2>>,
+    Minimum<D::CollisionResistance, Quot<N, U2>>: Unsigned,
+{
+    type CollisionResistance = Minimum<D::CollisionResistance, Quot<N, U2>>;
+}
+
+// this blanket impl is needed for HMAC
+impl<D, N, S, O> BlockSizeUser for Truncated<D, N, S, O>
+where
+    D: FixedOutput + BlockSizeUser,
+    N: ArraySize + IsLessOrEqual<D::OutputSize, Output = True>,
+    S: TruncationSide,
+{
+    type BlockSize = D::BlockSize;
+}
+
+impl<D, N, S, O> Reset for Truncated<D, N, S, O>
+where
+    D: FixedOutput + Reset,
+    N: ArraySize + IsLessOrEqual<D::OutputSize, Output = True>,
+    S: TruncationSide,
+{
+    fn reset(&mut self) {
+        self.hash.reset();
+    }
+}
+
+impl<D, N, S, O> Update for Truncated<D, N, S, O>
+where
+    D: FixedOutput,
+    N: ArraySize + IsLessOrEqual<D::OutputSize, Output = True>,
+    S: TruncationSide,
+{
+    fn update(&mut self, data: &[u8]) {
+        self.hash.update(data);
+    }
+}
+
+impl<D, N, S, O> OutputSizeUser for Truncated<D, N, S, O>
+where
+    D: FixedOutput,
+    N: ArraySize + IsLessOrEqual<D::OutputSize, Output = True>,
+    S: TruncationSide,
+{
+    type OutputSize = N;
+}
+
+impl<D, N, S, O> FixedOutput for Truncated<D, N, S, O>
+where
+    D: FixedOutput,
+    N: ArraySize + IsLessOrEqual<D::OutputSize, Output = True>,
+    S: TruncationSide,
+{
+    fn finalize_into(self, out: &mut Output<Self>) {
+        Self::truncate(&self.hash.finalize_fixed(), out);
+    }
+}
+
+impl<D, N, S, O> FixedOutputReset for Truncated<D, N, S, O>
+where
+    D: FixedOutputReset,
+    N: ArraySize + IsLessOrEqual<D::OutputSize, Output = True>,
+    S: TruncationSide,
+{
+    fn finalize_into_reset(&mut self, out: &mut Output<Self>) {
+        Self::truncate(&self.hash.finalize_fixed_reset(), out);
+    }
+}
+
+impl<D, N, S, O> AlgorithmName for Truncated<D, N, S, O>
+where
+    D: FixedOutput + AlgorithmName,
+    N: ArraySize + IsLessOrEqual<D::OutputSize, Output = True>,
+    S: TruncationSide,
+{
+    fn write_alg_name(f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        D::write_alg_name(f)?;
+        f.write_str("_")?;
+        write!(f, "{}", N::USIZE)
+    }
+}
+
+impl<D, N, S, O> fmt::Debug for Truncated<D, N, S, O>
+where
+    D: FixedOutput + fmt::Debug,
+    N: ArraySize + IsLessOrEqual<D::OutputSize, Output = True>,
+    S: TruncationSide,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Truncated")
+            .field("hash", &self.hash)
+            .field("size", &N::USIZE)
+            .finish()
+    }
+}
+
+#[cfg(feature = "oid")]
+impl<D, N, S, O> const_oid::AssociatedOid for Truncated<D, N, S, O>
+where
+    D: FixedOutput,
+    N: ArraySize + IsLessOrEqual<D::OutputSize, Output = True>,
+    S: TruncationSide,
+    O: const_oid::AssociatedOid,
+{
+    const OID: const_oid::ObjectIdentifier = O::OID;
+}
+
+#[cfg(feature = "zeroize")]
+impl<D, N, S, O> zeroize::ZeroizeOnDrop for Truncated<D, N, S, O>
+where
+    D: FixedOutput + zeroize::ZeroizeOnDrop,
+    N: ArraySize + IsLessOrEqual<D::OutputSize, Output = True>,
+    S: TruncationSide,
+{
+}
+
+impl<D, N, S, O> SerializableState for Truncated<D, N, S, O>
+where
+    D: FixedOutput + SerializableState,
+    N: ArraySize + IsLessOrEqual<D::OutputSize, Output = True>,
+    S: TruncationSide,
+{
+    type SerializedStateSize = D::SerializedStateSize;
+
+    fn serialize(&self) -> SerializedState<Self> {
+        self.hash.serialize()
+    }
+
+    fn deserialize(
+        serialized_state: &SerializedState<Self>,
+    ) -> Result<Self, DeserializeStateError> {
+        D::deserialize(serialized_state).map(Self::from_hash)
+    }
+}
+
+impl<D, N, S, O> StateVersion for Truncated<D, N, S, O>
+where
+    D: FixedOutput + StateVersion,
+    N: ArraySize + IsLessOrEqual<D::OutputSize, Output = True>,
+    S: TruncationSide,
+{
+    const STATE_VERSION: &'static str = D::STATE_VERSION;
+}
@@ -7,7 +7,7 @@ use common::{BlockSizeUser, KeyInit, KeySizeUser, OutputSizeUser, Reset};
 
 use crate::{
     CollisionResistance, CustomizedInit, ExtendableOutput, ExtendableOutputReset, FixedOutput,
-    FixedOutputReset, HashMarker, Update,
+    FixedOutputReset, HashMarker, StateVersion, Update,
 };
 
 /// Wrapper around [`ExtendableOutput`] types adding [`OutputSizeUser`] with the given size of `S`.
@@ -150,3 +150,25 @@ impl<T: ExtendableOutput + SerializableState, S: ArraySize> SerializableState
         })
     }
 }
+
+impl<T: ExtendableOutput + StateVersion, S: ArraySize> StateVersion for XofFixedWrapper<T, S> {
+    const STATE_VERSION: &'static str = T::STATE_VERSION;
+}
+
+#[cfg(feature = "serde")]
+impl<T: ExtendableOutput + SerializableState, S: ArraySize> serde::Serialize
+    for XofFixedWrapper<T, S>
+{
+    fn serialize<Ser: serde::Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        crate::serde_state::serialize(self, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: ExtendableOutput + SerializableState, S: ArraySize> serde::Deserialize<'de>
+    for XofFixedWrapper<T, S>
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        crate::serde_state::deserialize(deserializer)
+    }
+}
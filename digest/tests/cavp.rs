@@ -0,0 +1,106 @@
+//! Tests for the NIST CAVP test runners.
+
+#![cfg(all(feature = "dev", feature = "alloc"))]
+
+use digest::{
+    Digest,
+    dev::{CavpError, cavp_msg_test, parse_cavp},
+};
+use sha2::Sha256;
+use sha3::{Sha3_256, Shake128};
+
+digest::cavp_test!(sha256_short, Sha256, cavp_msg_test, "cavp_sha256_short");
+digest::cavp_test!(
+    sha256_monte,
+    Sha256,
+    cavp_sha2_monte_test,
+    "cavp_sha256_monte"
+);
+digest::cavp_test!(
+    sha3_256_monte,
+    Sha3_256,
+    cavp_sha3_monte_test,
+    "cavp_sha3_256_monte"
+);
+digest::cavp_test!(
+    shake128_short,
+    Shake128,
+    cavp_xof_test,
+    "cavp_shake128_short"
+);
+digest::cavp_test!(
+    shake128_variable,
+    Shake128,
+    cavp_xof_test,
+    "cavp_shake128_variable"
+);
+digest::cavp_test!(
+    shake128_monte,
+    Shake128,
+    cavp_shake_monte_test,
+    "cavp_shake128_monte"
+);
+
+#[test]
+fn cavp_parse() {
+    let rsp = "# comment\n\n[L = 32]\n[Tested for byte-oriented messages]\n\nLen = 8\nMsg = d3\n\n\
+        Len = 16\nMsg = abcd\n[L = 64]\n\nSeed = 00\n";
+    let records = parse_cavp(rsp).unwrap();
+    assert_eq!(records.len(), 3);
+    assert_eq!(records[0].line, 6);
+    assert_eq!(records[0].field("Msg"), Some("d3"));
+    assert_eq!(records[0].num_field("Len"), Ok(8));
+    assert_eq!(
+        records[1].params,
+        [("L", "32"), ("Tested for byte-oriented messages", "")]
+    );
+    assert_eq!(records[1].hex_field("Msg"), Ok(vec![0xab, 0xcd]));
+    assert_eq!(records[2].params, [("L", "64")]);
+    assert_eq!(records[2].param("L"), Some("64"));
+
+    let err = parse_cavp("[L = 32]\n\nLen 8\n").unwrap_err();
+    assert_eq!(err.line, 3);
+}
+
+#[test]
+fn cavp_failures() {
+    let check = |rsp: &str, reason| {
+        assert_eq!(
+            cavp_msg_test::<Sha256>(rsp).map_err(|err| err.reason),
+            Err(reason)
+        );
+    };
+
+    let md = Sha256::digest([0xd3]);
+    let mut bad_md = md;
+    bad_md[0] ^= 1;
+    check(
+        &format!("Len = 8\nMsg = d3\nMD = {}\n", hex(&bad_md)),
+        "whole message",
+    );
+    check(
+        &format!("Len = 7\nMsg = d3\nMD = {}\n", hex(&md)),
+        "bit-oriented messages are not supported",
+    );
+    check(
+        &format!("Len = 8\nMsg = d3\nMD = {}\n", hex(&md[..20])),
+        "output size mismatch",
+    );
+    check("Len = 8\nMsg = d\nMD = 00\n", "invalid hex value");
+    check("# empty\n", "no test records");
+
+    let rsp = format!("[L = 32]\n\nLen = 8\nMsg = d3\nMD = {}\n", hex(&md));
+    assert_eq!(cavp_msg_test::<Sha256>(&rsp), Ok(()));
+    let err = CavpError {
+        line: 1,
+        reason: "missing seed",
+    };
+    assert_eq!(
+        digest::dev::cavp_sha2_monte_test::<Sha256>("COUNT = 0\nMD = 00\n"),
+        Err(err)
+    );
+}
+
+fn hex(data: &[u8]) -> String {
+    data.iter().map(|b| format!("{b:02x}")).collect()
+}
@@ -0,0 +1,107 @@
+//! Tests for hasher checkpoints.
+
+#![cfg(feature = "alloc")]
+
+use digest::{
+    Digest,
+    checkpoint::{self, CheckpointError},
+};
+use sha2::{Sha256, Sha512};
+
+#[test]
+fn checkpoint_roundtrip() {
+    let msg = b"partially hashed upload";
+    for pos in 0..msg.len() {
+        let hasher = Sha256::new_with_prefix(&msg[..pos]);
+        let cp = checkpoint::save(&hasher, pos as u64);
+        assert_eq!(&cp[..4], b"DGCP");
+
+        let (mut restored, position) = checkpoint::restore::<Sha256>(&cp).unwrap();
+        assert_eq!(position, pos as u64);
+        restored.update(&msg[pos..]);
+        assert_eq!(restored.finalize(), Sha256::digest(msg));
+    }
+}
+
+#[test]
+fn checkpoint_errors() {
+    let hasher = Sha256::new_with_prefix(b"data");
+    let cp = checkpoint::save(&hasher, 4);
+
+    let res = checkpoint::restore::<Sha512>(&cp);
+    assert_eq!(res.err(), Some(CheckpointError::AlgorithmMismatch));
+
+    // corruption of any byte is detected
+    for i in 0..cp.len() {
+        let mut bad = cp.clone();
+        bad[i] ^= 0x01;
+        assert!(checkpoint::restore::<Sha256>(&bad).is_err());
+    }
+    let mut bad = cp.clone();
+    bad[20] ^= 0x01;
+    let res = checkpoint::restore::<Sha256>(&bad);
+    assert_eq!(res.err(), Some(CheckpointError::ChecksumMismatch));
+    let mut bad = cp.clone();
+    bad[4] = 2;
+    let res = checkpoint::restore::<Sha256>(&bad);
+    assert_eq!(res.err(), Some(CheckpointError::UnsupportedFormat));
+
+    for len in 0..cp.len() {
+        assert!(checkpoint::restore::<Sha256>(&cp[..len]).is_err());
+    }
+    let res = checkpoint::restore::<Sha256>(&[]);
+    assert_eq!(res.err(), Some(CheckpointError::Malformed));
+}
+
+#[cfg(feature = "block-api")]
+#[test]
+fn checkpoint_state_version() {
+    use digest::{StateVersion, block_api::CtOutWrapper, consts::U32};
+
+    digest::buffer_fixed!(
+        /// SHA-256 hasher defined outside of the `sha2` crate.
+        pub struct LocalSha256(CtOutWrapper<sha2::block_api::Sha256VarCore, U32>);
+        impl: BaseFixedTraits AlgorithmName Default Clone HashMarker SerializableState;
+    );
+
+    assert_eq!(
+        LocalSha256::STATE_VERSION,
+        concat!("digest ", env!("CARGO_PKG_VERSION"))
+    );
+    assert!(Sha256::STATE_VERSION.starts_with("sha2 "));
+
+    // same algorithm name and state size, but a different implementation crate
+    let cp = checkpoint::save(&Sha256::new_with_prefix(b"data"), 4);
+    let res = checkpoint::restore::<LocalSha256>(&cp);
+    assert_eq!(res.err(), Some(CheckpointError::StateVersionMismatch));
+    let cp = checkpoint::save(&LocalSha256::new_with_prefix(b"data"), 4);
+    let res = checkpoint::restore::<Sha256>(&cp);
+    assert_eq!(res.err(), Some(CheckpointError::StateVersionMismatch));
+}
+
+#[cfg(feature = "oid")]
+#[test]
+fn checkpoint_oid() {
+    use digest::{block_api::CtOutWrapper, consts::U32};
+
+    digest::buffer_fixed!(
+        /// SHA-256 hasher with associated OID.
+        pub struct OidSha256(CtOutWrapper<sha2::block_api::Sha256VarCore, U32>);
+        oid: "2.16.840.1.101.3.4.2.1";
+        impl: BaseFixedTraits AlgorithmName Default Clone HashMarker SerializableState;
+    );
+
+    let hasher = OidSha256::new_with_prefix(b"data");
+    let cp = checkpoint::save_with_oid(&hasher, 4);
+    let (restored, position) = checkpoint::restore_with_oid::<OidSha256>(&cp).unwrap();
+    assert_eq!(position, 4);
+    assert_eq!(restored.finalize(), Sha256::digest(b"data"));
+    // OID is ignored by `restore`
+    let (restored, _) = checkpoint::restore::<OidSha256>(&cp).unwrap();
+    assert_eq!(restored.finalize(), Sha256::digest(b"data"));
+
+    // checkpoint without OID
+    let cp = checkpoint::save(&hasher, 4);
+    let res = checkpoint::restore_with_oid::<OidSha256>(&cp);
+    assert_eq!(res.err(), Some(CheckpointError::AlgorithmMismatch));
+}
@@ -125,3 +125,38 @@ const _: () = {
     check_zeroize::<FixedHashWithSer>();
     check_zeroize::<FixedHashWithOidSer>();
 };
+
+#[cfg(feature = "serde")]
+#[test]
+fn fixed_hash_serde() {
+    use digest::{Digest, common::hazmat::SerializableState};
+
+    let mut hasher = FixedHashWithSer::new();
+    hasher.update(b"partially hashed");
+    let state = hasher.serialize();
+
+    let json = serde_json::to_string(&hasher).unwrap();
+    let hex: String = state.iter().map(|b| format!("{b:02x}")).collect();
+    assert_eq!(json, format!("\"{hex}\""));
+    let from_json: FixedHashWithSer = serde_json::from_str(&json).unwrap();
+    assert_eq!(from_json.serialize(), state);
+
+    let mut cbor = Vec::new();
+    ciborium::into_writer(&hasher, &mut cbor).unwrap();
+    // CBOR byte string header followed by raw state bytes
+    assert_eq!(&cbor[1..], &state[..]);
+    let from_cbor: FixedHashWithSer = ciborium::from_reader(&cbor[..]).unwrap();
+    assert_eq!(from_cbor.serialize(), state);
+
+    let expected = hasher.chain_update(b" stream").finalize();
+    assert_eq!(from_json.chain_update(b" stream").finalize(), expected);
+    assert_eq!(from_cbor.chain_update(b" stream").finalize(), expected);
+
+    let upper = serde_json::to_string(&hex.to_uppercase()).unwrap();
+    assert!(serde_json::from_str::<FixedHashWithSer>(&upper).is_ok());
+    let short = format!("\"{}\"", &hex[..hex.len() - 2]);
+    assert!(serde_json::from_str::<FixedHashWithSer>(&short).is_err());
+    assert!(serde_json::from_str::<FixedHashWithSer>("\"zz\"").is_err());
+    let bad_buf_pos = format!("\"{}ff\"", &hex[..hex.len() - 2]);
+    assert!(serde_json::from_str::<FixedHashWithSer>(&bad_buf_pos).is_err());
+}
@@ -0,0 +1,62 @@
+//! Tests for the object-safe `DynXof` trait.
+
+#![cfg(feature = "alloc")]
+
+use digest::{DynXof, ExtendableOutput, Update};
+use sha3::Shake128;
+
+fn expected(data: &[u8], len: usize) -> Box<[u8]> {
+    let mut hasher = Shake128::default();
+    Update::update(&mut hasher, data);
+    hasher.finalize_boxed(len)
+}
+
+#[test]
+fn dyn_xof_finalize() {
+    let mut hasher: Box<dyn DynXof> = Box::new(Shake128::default());
+    hasher.update(b"hello ");
+    hasher.update(b"world");
+    let mut reader = hasher.finalize_xof_boxed();
+    let mut out = [0u8; 100];
+    reader.read(&mut out[..10]);
+    reader.read(&mut out[10..]);
+    assert_eq!(out[..], *expected(b"hello world", 100));
+}
+
+#[test]
+fn dyn_xof_reset() {
+    let mut hasher: Box<dyn DynXof> = Box::new(Shake128::default());
+    hasher.update(b"abc");
+    let out = hasher.finalize_xof_reset_boxed().read_boxed(40);
+    assert_eq!(out, expected(b"abc", 40));
+
+    hasher.update(b"abc");
+    let mut out = [0u8; 40];
+    hasher.finalize_xof_reset_into(&mut out);
+    assert_eq!(out[..], *expected(b"abc", 40));
+
+    hasher.update(b"garbage");
+    hasher.reset();
+    hasher.update(b"abc");
+    assert_eq!(
+        hasher.finalize_xof_boxed().read_boxed(40),
+        expected(b"abc", 40)
+    );
+}
+
+#[test]
+fn dyn_xof_clone() {
+    let mut hasher: Box<dyn DynXof> = Box::new(Shake128::default());
+    hasher.update(b"prefix");
+    let mut cloned = hasher.clone();
+    hasher.update(b" one");
+    cloned.update(b" two");
+    assert_eq!(
+        hasher.finalize_xof_boxed().read_boxed(64),
+        expected(b"prefix one", 64)
+    );
+    assert_eq!(
+        cloned.finalize_xof_boxed().read_boxed(64),
+        expected(b"prefix two", 64)
+    );
+}
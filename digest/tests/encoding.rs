@@ -0,0 +1,162 @@
+//! Tests for the SP 800-185 encodings.
+
+use digest::{
+    Digest, Update,
+    encoding::{Tuple, bytepad, encode_string, left_encode, right_encode},
+};
+use sha2::Sha256;
+
+/// Hasher which records all input data
+#[derive(Default)]
+struct Recorder(Vec<u8>);
+
+impl Update for Recorder {
+    fn update(&mut self, data: &[u8]) {
+        self.0.extend_from_slice(data);
+    }
+}
+
+#[test]
+fn int_encodings() {
+    assert_eq!(left_encode(0).as_bytes(), [1, 0]);
+    assert_eq!(right_encode(0).as_bytes(), [0, 1]);
+    assert_eq!(left_encode(255).as_bytes(), [1, 255]);
+    assert_eq!(right_encode(255).as_bytes(), [255, 1]);
+    assert_eq!(left_encode(256).as_bytes(), [2, 1, 0]);
+    assert_eq!(right_encode(256).as_bytes(), [1, 0, 2]);
+    assert_eq!(
+        left_encode(0x0102_0304_0506).as_bytes(),
+        [6, 1, 2, 3, 4, 5, 6]
+    );
+    assert_eq!(
+        left_encode(u64::MAX).as_bytes(),
+        [8, 255, 255, 255, 255, 255, 255, 255, 255]
+    );
+    assert_eq!(
+        right_encode(u64::MAX).as_bytes(),
+        [255, 255, 255, 255, 255, 255, 255, 255, 8]
+    );
+}
+
+#[test]
+fn string_encoding() {
+    let mut r = Recorder::default();
+    encode_string(&mut r, b"");
+    encode_string(&mut r, b"abc");
+    assert_eq!(r.0, [1, 0, 1, 24, b'a', b'b', b'c']);
+
+    let mut r = Recorder::default();
+    encode_string(&mut r, &[0u8; 32]);
+    assert_eq!(r.0[..3], [2, 1, 0]);
+    assert_eq!(r.0.len(), 35);
+}
+
+#[test]
+fn bytepad_encoding() {
+    let mut r = Recorder::default();
+    bytepad(&mut r, 8, &[b"ab", b"c"]);
+    assert_eq!(r.0, [1, 8, b'a', b'b', b'c', 0, 0, 0]);
+
+    let mut r = Recorder::default();
+    bytepad(&mut r, 4, &[b"ab"]);
+    assert_eq!(r.0, [1, 4, b'a', b'b']);
+
+    let mut r = Recorder::default();
+    bytepad(&mut r, 168, &[&[0xAA; 200]]);
+    assert_eq!(r.0.len(), 336);
+    assert_eq!(r.0[..2], [1, 168]);
+    assert!(r.0[202..].iter().all(|&b| b == 0));
+}
+
+#[test]
+fn tuple_encoding() {
+    let r = Tuple::new(Recorder::default())
+        .field(b"ab")
+        .list([&b"c"[..], b""])
+        .finish(256);
+    assert_eq!(r.0, [1, 16, b'a', b'b', 1, 2, 1, 8, b'c', 1, 0, 1, 0, 2]);
+
+    let mut t = Tuple::new(Recorder::default());
+    t.update_field("ab");
+    t.update_list(Vec::<&[u8]>::new());
+    assert_eq!(t.into_inner().0, [1, 16, b'a', b'b', 1, 0]);
+}
+
+#[test]
+fn tuple_is_unambiguous() {
+    let hash = |fields: &[&[u8]]| {
+        let mut t = Tuple::new(Sha256::new());
+        for field in fields {
+            t.update_field(field);
+        }
+        t.into_inner().finalize()
+    };
+    assert_ne!(hash(&[b"ab", b"c"]), hash(&[b"a", b"bc"]));
+    assert_ne!(hash(&[b"abc"]), hash(&[b"abc", b""]));
+    assert_ne!(hash(&[b"", b"abc"]), hash(&[b"abc", b""]));
+    assert_eq!(hash(&[b"a", b"bc"]), hash(&[b"a", b"bc"]));
+}
+
+#[cfg(feature = "block-api")]
+mod tuple_hash {
+    use super::Recorder;
+    use digest::{
+        ExtendableOutput,
+        consts::{U0, U168},
+        encoding::{Tuple, bytepad, encode_string},
+    };
+    use hex_literal::hex;
+    use sha3::block_api::{Sha3HasherCore, Sha3ReaderCore};
+
+    digest::buffer_xof!(
+        /// Keccak sponge with the cSHAKE128 rate and padding
+        pub struct CShake128(Sha3HasherCore<U168, U0, 0x04>);
+        impl: XofHasherTraits;
+        /// Reader of [`CShake128`]
+        pub struct CShake128Reader(Sha3ReaderCore<U168>);
+        impl: XofReaderTraits;
+    );
+
+    /// Initialize cSHAKE128 with function name `n` and customization string `s`.
+    fn cshake128(n: &[u8], s: &[u8]) -> CShake128 {
+        let mut prefix = Recorder::default();
+        encode_string(&mut prefix, n);
+        encode_string(&mut prefix, s);
+        let mut hasher = CShake128::default();
+        bytepad(&mut hasher, 168, &[&prefix.0]);
+        hasher
+    }
+
+    /// Compute 256-bit TupleHash128 of `fields` with customization string `s`.
+    fn tuple_hash128(fields: &[&[u8]], s: &[u8]) -> [u8; 32] {
+        let tuple = fields
+            .iter()
+            .fold(Tuple::new(cshake128(b"TupleHash", s)), |t, field| {
+                t.field(field)
+            });
+        let mut out = [0u8; 32];
+        tuple.finish(256).finalize_xof_into(&mut out);
+        out
+    }
+
+    /// TupleHash128 samples from the NIST SP 800-185 examples
+    #[test]
+    fn tuple_hash128_samples() {
+        let x1 = &hex!("000102")[..];
+        let x2 = &hex!("101112131415")[..];
+        let x3 = &hex!("202122232425262728")[..];
+
+        assert_eq!(
+            tuple_hash128(&[x1, x2], b""),
+            hex!("c5d8786c1afb9b82111ab34b65b2c0048fa64e6d48e263264ce1707d3ffc8ed1"),
+        );
+        assert_eq!(
+            tuple_hash128(&[x1, x2], b"My Tuple App"),
+            hex!("75cdb20ff4db1154e841d758e24160c54bae86eb8c13e7f5f40eb35588e96dfb"),
+        );
+        assert_eq!(
+            tuple_hash128(&[x1, x2, x3], b"My Tuple App"),
+            hex!("e60f202c89a2631eda8d4c588ca5fd07f39e5151998deccf973adb3804bb6e84"),
+        );
+    }
+}
@@ -0,0 +1,181 @@
+//! Tests for the incremental-consistency checks.
+
+#![cfg(feature = "dev")]
+
+use digest::{
+    Digest, FixedOutput, HashMarker, Output, OutputSizeUser, Update,
+    common::BlockSizeUser,
+    consts::{U32, U64},
+    dev::{
+        incremental_digest_reset_test, incremental_digest_test, incremental_serialization_test,
+        incremental_xof_reset_test, incremental_xof_serialization_test, incremental_xof_test,
+    },
+};
+#[cfg(feature = "mac")]
+use digest::{
+    FixedOutputReset, Key, KeyInit, MacMarker, Reset,
+    common::{
+        KeySizeUser,
+        array::Array,
+        hazmat::{DeserializeStateError, SerializableState, SerializedState},
+        typenum::Sum,
+    },
+    dev::{incremental_mac_reset_test, incremental_mac_serialization_test, incremental_mac_test},
+};
+use sha2::{Sha256, Sha512};
+use sha3::Shake128;
+
+/// HMAC-SHA-256 implemented on top of the `Digest` API
+#[cfg(feature = "mac")]
+#[derive(Clone)]
+struct TestHmac {
+    key: [u8; 64],
+    inner: Sha256,
+}
+
+#[cfg(feature = "mac")]
+impl TestHmac {
+    fn new_inner(key: &[u8; 64]) -> Sha256 {
+        Sha256::new().chain_update(key.map(|b| b ^ 0x36))
+    }
+}
+
+#[cfg(feature = "mac")]
+impl KeySizeUser for TestHmac {
+    type KeySize = U64;
+}
+
+#[cfg(feature = "mac")]
+impl KeyInit for TestHmac {
+    fn new(key: &Key<Self>) -> Self {
+        let key = key.0;
+        let inner = Self::new_inner(&key);
+        Self { key, inner }
+    }
+}
+
+#[cfg(feature = "mac")]
+impl BlockSizeUser for TestHmac {
+    type BlockSize = U64;
+}
+
+#[cfg(feature = "mac")]
+impl Update for TestHmac {
+    fn update(&mut self, data: &[u8]) {
+        Digest::update(&mut self.inner, data);
+    }
+}
+
+#[cfg(feature = "mac")]
+impl OutputSizeUser for TestHmac {
+    type OutputSize = U32;
+}
+
+#[cfg(feature = "mac")]
+impl FixedOutput for TestHmac {
+    fn finalize_into(self, out: &mut Output<Self>) {
+        let h = Sha256::new()
+            .chain_update(self.key.map(|b| b ^ 0x5c))
+            .chain_update(self.inner.finalize());
+        FixedOutput::finalize_into(h, out);
+    }
+}
+
+#[cfg(feature = "mac")]
+impl Reset for TestHmac {
+    fn reset(&mut self) {
+        self.inner = Self::new_inner(&self.key);
+    }
+}
+
+#[cfg(feature = "mac")]
+impl FixedOutputReset for TestHmac {
+    fn finalize_into_reset(&mut self, out: &mut Output<Self>) {
+        self.clone().finalize_into(out);
+        Reset::reset(self);
+    }
+}
+
+#[cfg(feature = "mac")]
+impl MacMarker for TestHmac {}
+
+#[cfg(feature = "mac")]
+impl SerializableState for TestHmac {
+    type SerializedStateSize = Sum<<Sha256 as SerializableState>::SerializedStateSize, U64>;
+
+    fn serialize(&self) -> SerializedState<Self> {
+        self.inner
+            .serialize()
+            .concat(Array::<u8, U64>::from(self.key))
+    }
+
+    fn deserialize(
+        serialized_state: &SerializedState<Self>,
+    ) -> Result<Self, DeserializeStateError> {
+        let (inner, key) = serialized_state.split_ref();
+        Ok(Self {
+            key: key.0,
+            inner: Sha256::deserialize(inner)?,
+        })
+    }
+}
+
+/// SHA-256 wrapper which mishandles block-sized updates
+#[derive(Clone, Default)]
+struct BuggySha256(Sha256);
+
+impl BlockSizeUser for BuggySha256 {
+    type BlockSize = U64;
+}
+
+impl Update for BuggySha256 {
+    fn update(&mut self, data: &[u8]) {
+        match data.len() {
+            64 => Digest::update(&mut self.0, &data[1..]),
+            _ => Digest::update(&mut self.0, data),
+        }
+    }
+}
+
+impl OutputSizeUser for BuggySha256 {
+    type OutputSize = U32;
+}
+
+impl FixedOutput for BuggySha256 {
+    fn finalize_into(self, out: &mut Output<Self>) {
+        FixedOutput::finalize_into(self.0, out);
+    }
+}
+
+impl HashMarker for BuggySha256 {}
+
+#[test]
+fn incremental_sha2() {
+    assert_eq!(incremental_digest_test::<Sha256>(), Ok(()));
+    assert_eq!(incremental_digest_test::<Sha512>(), Ok(()));
+    assert_eq!(incremental_digest_reset_test::<Sha256>(), Ok(()));
+    assert_eq!(incremental_serialization_test::<Sha256>(), Ok(()));
+}
+
+#[test]
+fn incremental_xof() {
+    assert_eq!(incremental_xof_test::<Shake128>(), Ok(()));
+    assert_eq!(incremental_xof_reset_test::<Shake128>(), Ok(()));
+    assert_eq!(incremental_xof_serialization_test::<Shake128>(), Ok(()));
+}
+
+#[test]
+#[cfg(feature = "mac")]
+fn incremental_mac() {
+    assert_eq!(incremental_mac_test::<TestHmac>(), Ok(()));
+    assert_eq!(incremental_mac_reset_test::<TestHmac>(), Ok(()));
+    assert_eq!(incremental_mac_serialization_test::<TestHmac>(), Ok(()));
+}
+
+#[test]
+fn incremental_detects_bugs() {
+    assert_eq!(
+        incremental_digest_test::<BuggySha256>(),
+        Err("message in chunks")
+    );
+}
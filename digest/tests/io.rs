@@ -0,0 +1,82 @@
+//! Tests for `std::io` compatibility wrappers.
+
+#![cfg(feature = "std")]
+
+use digest::{Digest, HashReader, HashWriter};
+use sha2::Sha256;
+use std::io::{self, Read, Write};
+
+/// Reader which returns data in small chunks and interrupts every other call.
+struct ChunkedReader<'a> {
+    data: &'a [u8],
+    interrupt: bool,
+}
+
+impl Read for ChunkedReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.interrupt = !self.interrupt;
+        if self.interrupt {
+            return Err(io::ErrorKind::Interrupted.into());
+        }
+        let n = buf.len().min(self.data.len()).min(7);
+        let (head, tail) = self.data.split_at(n);
+        buf[..n].copy_from_slice(head);
+        self.data = tail;
+        Ok(n)
+    }
+}
+
+fn msg() -> Vec<u8> {
+    (0..20_000u32).flat_map(u32::to_le_bytes).collect()
+}
+
+#[test]
+fn hash_writer() {
+    let msg = msg();
+    let mut writer = HashWriter::new(Sha256::new());
+    for chunk in msg.chunks(1000) {
+        writer.write_all(chunk).unwrap();
+    }
+    writer.flush().unwrap();
+    assert_eq!(writer.into_inner().finalize(), Sha256::digest(&msg));
+
+    let mut writer = HashWriter::new(Sha256::new());
+    io::copy(&mut &msg[..], &mut writer).unwrap();
+    assert_eq!(writer.get_ref().clone().finalize(), Sha256::digest(&msg));
+}
+
+#[test]
+fn hash_reader() {
+    let msg = msg();
+    let mut reader = HashReader::new(&msg[..], Sha256::new());
+    let mut out = Vec::new();
+    reader.read_to_end(&mut out).unwrap();
+    assert_eq!(out, msg);
+    let (rest, hasher) = reader.into_inner();
+    assert!(rest.is_empty());
+    assert_eq!(hasher.finalize(), Sha256::digest(&msg));
+
+    // partially read data is hashed as well
+    let mut reader = HashReader::new(&msg[..], Sha256::new());
+    let mut buf = [0u8; 100];
+    reader.read_exact(&mut buf).unwrap();
+    assert_eq!(reader.get_reader().len(), msg.len() - buf.len());
+    assert_eq!(reader.into_hasher().finalize(), Sha256::digest(&msg[..100]));
+}
+
+#[test]
+fn digest_reader() {
+    let msg = msg();
+    let hash = Sha256::digest_reader(&msg[..]).unwrap();
+    assert_eq!(hash, Sha256::digest(&msg));
+
+    let reader = ChunkedReader {
+        data: &msg,
+        interrupt: false,
+    };
+    let mut hasher = Sha256::new_with_prefix(b"prefix");
+    let n = hasher.update_reader(reader).unwrap();
+    assert_eq!(n, 80_000);
+    let expected = Sha256::new_with_prefix(b"prefix").chain_update(&msg);
+    assert_eq!(hasher.finalize(), expected.finalize());
+}
@@ -0,0 +1,157 @@
+//! Tests for multi-buffer hashing.
+
+#![cfg(feature = "block-api")]
+
+mod block_api {
+    use core::cell::Cell;
+    use digest::{
+        HashMarker, Output, OutputSizeUser, Reset,
+        array::Array,
+        block_api::{
+            Block, BlockSizeUser, Buffer, BufferKindUser, CtOutWrapper, Eager, FixedOutputCore,
+            ParBlocks, ParBlocksSizeUser, ParUpdateCore, UpdateCore,
+        },
+        consts::{U4, U32, U64},
+    };
+    use sha2::block_api::Sha256VarCore;
+
+    std::thread_local! {
+        /// Number of `update_par_blocks` calls
+        pub static PAR_CALLS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    /// SHA-256 core with four lanes for testing purposes
+    #[derive(Clone, Default, Debug)]
+    pub struct LaneSha256Core(CtOutWrapper<Sha256VarCore, U32>);
+
+    impl BlockSizeUser for LaneSha256Core {
+        type BlockSize = U64;
+    }
+
+    impl BufferKindUser for LaneSha256Core {
+        type BufferKind = Eager;
+    }
+
+    impl OutputSizeUser for LaneSha256Core {
+        type OutputSize = U32;
+    }
+
+    impl HashMarker for LaneSha256Core {}
+
+    impl Reset for LaneSha256Core {
+        fn reset(&mut self) {
+            self.0.reset();
+        }
+    }
+
+    impl UpdateCore for LaneSha256Core {
+        fn update_blocks(&mut self, blocks: &[Block<Self>]) {
+            self.0.update_blocks(blocks);
+        }
+    }
+
+    impl FixedOutputCore for LaneSha256Core {
+        fn finalize_fixed_core(&mut self, buffer: &mut Buffer<Self>, out: &mut Output<Self>) {
+            self.0.finalize_fixed_core(buffer, out);
+        }
+    }
+
+    impl ParBlocksSizeUser for LaneSha256Core {
+        type ParBlocksSize = U4;
+    }
+
+    impl ParUpdateCore for LaneSha256Core {
+        fn update_par_blocks(lanes: &mut Array<Self, U4>, blocks: &ParBlocks<Self>) {
+            PAR_CALLS.with(|calls| calls.set(calls.get() + 1));
+            for (lane, block) in lanes.iter_mut().zip(blocks.iter()) {
+                lane.update_blocks(core::slice::from_ref(block));
+            }
+        }
+    }
+}
+
+digest::buffer_fixed!(
+    /// SHA-256 hasher with four lanes for testing purposes
+    pub struct LaneSha256(block_api::LaneSha256Core);
+    impl: BaseFixedTraits Default Clone HashMarker Reset FixedOutputReset;
+);
+
+use block_api::PAR_CALLS;
+use digest::{Digest, array::Array, multi::MultiDigest};
+use sha2::Sha256;
+
+fn msg(len: usize) -> Vec<u8> {
+    (0..=250u8).cycle().take(len).collect()
+}
+
+#[test]
+fn multi_digest_equal_lengths() {
+    let msgs: [Vec<u8>; 4] = [msg(300), msg(300), msg(300), msg(300)];
+    let mut hasher = MultiDigest::<LaneSha256>::new();
+    PAR_CALLS.with(|calls| calls.set(0));
+    hasher.update(&Array::from_fn(|i| msgs[i].as_slice()));
+    assert_eq!(PAR_CALLS.with(core::cell::Cell::get), 4);
+
+    for (out, m) in hasher.finalize().iter().zip(&msgs) {
+        assert_eq!(out[..], Sha256::digest(m)[..]);
+    }
+}
+
+#[test]
+fn multi_digest_incremental() {
+    let msgs: [Vec<u8>; 4] = [msg(0), msg(63), msg(200), msg(1000)];
+    let mut hasher = MultiDigest::<LaneSha256>::new();
+    let steps = [0usize, 1, 5, 64, 70, 129, 1000];
+    let mut prev = 0;
+    for step in steps {
+        hasher.update(&Array::from_fn(|i| {
+            let m = &msgs[i];
+            &m[prev.min(m.len())..step.min(m.len())]
+        }));
+        prev = step;
+    }
+
+    let res = hasher.finalize_reset();
+    for (out, m) in res.iter().zip(&msgs) {
+        assert_eq!(out[..], Sha256::digest(m)[..]);
+    }
+
+    let empty = Sha256::digest([]);
+    for out in hasher.finalize() {
+        assert_eq!(out[..], empty[..]);
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn multi_digest_many() {
+    let msgs: Vec<Vec<u8>> = [0usize, 1, 64, 65, 100, 1000, 3, 128, 129]
+        .iter()
+        .map(|&len| msg(len))
+        .collect();
+    let refs: Vec<&[u8]> = msgs.iter().map(Vec::as_slice).collect();
+    let res = MultiDigest::<LaneSha256>::digest_many(&refs);
+    assert_eq!(res.len(), msgs.len());
+    for (out, m) in res.iter().zip(&msgs) {
+        assert_eq!(out[..], Sha256::digest(m)[..]);
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn multi_digest_many_refills_lanes() {
+    // The first message ends after one block, after which the fifth message
+    // is hashed in its lane in parallel with the rest of the other messages
+    let msgs: Vec<Vec<u8>> = [64usize, 640, 640, 640, 576]
+        .iter()
+        .map(|&len| msg(len))
+        .collect();
+    let refs: Vec<&[u8]> = msgs.iter().map(Vec::as_slice).collect();
+    PAR_CALLS.with(|calls| calls.set(0));
+    let res = MultiDigest::<LaneSha256>::digest_many(&refs);
+    assert_eq!(PAR_CALLS.with(core::cell::Cell::get), 10);
+    assert_eq!(res.len(), msgs.len());
+    for (out, m) in res.iter().zip(&msgs) {
+        assert_eq!(out[..], Sha256::digest(m)[..]);
+    }
+}
@@ -0,0 +1,79 @@
+//! Tests for the runtime hash registry.
+
+#![cfg(feature = "alloc")]
+
+use digest::{Digest, block_api::CtOutWrapper, consts::U32, registry::Registry};
+use sha2::{Sha256, Sha512};
+
+digest::buffer_fixed!(
+    /// SHA-256 hasher with associated OID.
+    pub struct OidSha256(CtOutWrapper<sha2::block_api::Sha256VarCore, U32>);
+    oid: "2.16.840.1.101.3.4.2.1";
+    impl: BaseFixedTraits AlgorithmName Default Clone HashMarker Reset FixedOutputReset;
+);
+
+fn hash(registry: &Registry, name: &str, data: &[u8]) -> Option<Box<[u8]>> {
+    let mut hasher = registry.get_by_name(name)?;
+    hasher.update(data);
+    Some(hasher.finalize())
+}
+
+#[test]
+fn registry_by_name() {
+    let mut registry = Registry::new();
+    registry.register::<Sha256>();
+    registry.register::<Sha512>();
+    registry.register_constructor("SHA-256", || Box::new(Sha256::new()));
+    assert_eq!(
+        registry.names().collect::<Vec<_>>(),
+        ["Sha256_32", "Sha512_64", "SHA-256"]
+    );
+
+    // names are provided by the `AlgorithmName` implementations
+    let data = b"registry";
+    let expected = Sha256::digest(data);
+    assert_eq!(
+        hash(&registry, "Sha256_32", data).unwrap()[..],
+        expected[..]
+    );
+    assert_eq!(hash(&registry, "sha-256", data).unwrap()[..], expected[..]);
+    let expected = Sha512::digest(data);
+    assert_eq!(
+        hash(&registry, "SHA512_64", data).unwrap()[..],
+        expected[..]
+    );
+
+    assert!(registry.get_by_name("Sha384").is_none());
+    assert!(!registry.contains_name("Sha384"));
+
+    // registration with the same name replaces the previous entry
+    registry.register_constructor("sha-256", || Box::new(Sha512::new()));
+    assert_eq!(registry.names().count(), 3);
+    assert_eq!(registry.get_by_name("SHA-256").unwrap().output_size(), 64);
+}
+
+#[cfg(feature = "oid")]
+#[test]
+fn registry_by_oid() {
+    use digest::const_oid::{AssociatedOid, ObjectIdentifier};
+
+    const SHA512_OID: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.16.840.1.101.3.4.2.3");
+
+    let mut registry = Registry::new();
+    registry.register_with_oid::<OidSha256>();
+    registry.register_constructor_with_oid("SHA-512", SHA512_OID, || Box::new(Sha512::new()));
+
+    let mut hasher = registry.get_by_oid(&OidSha256::OID).unwrap();
+    hasher.update(b"oid");
+    assert_eq!(hasher.finalize()[..], Sha256::digest(b"oid")[..]);
+    assert_eq!(registry.get_by_oid(&SHA512_OID).unwrap().output_size(), 64);
+
+    let unknown = ObjectIdentifier::new_unwrap("2.16.840.1.101.3.4.2.2");
+    assert!(registry.get_by_oid(&unknown).is_none());
+    assert!(registry.contains_name("Sha256_32"));
+
+    // registration without OID keeps the previously registered OID
+    registry.register::<OidSha256>();
+    assert_eq!(registry.names().count(), 2);
+    assert!(registry.get_by_oid(&OidSha256::OID).is_some());
+}
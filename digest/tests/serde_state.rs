@@ -0,0 +1,100 @@
+//! Serde round-trip tests for hashers generated by the buffer macros.
+
+#![cfg(all(feature = "serde", feature = "block-api"))]
+#![allow(clippy::unwrap_used, reason = "tests")]
+
+use digest::{
+    Digest, ExtendableOutput, Update, XofFixedWrapper,
+    common::hazmat::SerializableState,
+    consts::{U0, U28, U32, U168},
+    serde::{Serialize, de::DeserializeOwned},
+};
+use sha3::block_api::{Sha3HasherCore, Sha3ReaderCore};
+
+digest::buffer_xof!(
+    /// SHAKE128 hasher with `serde` support
+    pub struct TestShake128(Sha3HasherCore<U168, U0, 0x1f>);
+    impl: XofHasherTraits;
+    /// Reader of [`TestShake128`]
+    pub struct TestShake128Reader(Sha3ReaderCore<U168>);
+    impl: XofReaderTraits;
+);
+
+digest::buffer_ct_variable!(
+    /// SHA-256 hasher with variable output size and `serde` support
+    pub struct TestSha256<OutSize>(sha2::block_api::Sha256VarCore);
+    max_size: U32;
+);
+
+const HEAD: &[u8] = b"partially hashed";
+const TAIL: &[u8] = b" stream";
+
+/// Check that `hasher` survives JSON and CBOR round-trips and that the restored
+/// hashers produce the same result as the original one after processing `TAIL`.
+fn check_round_trip<T, R>(hasher: T, finalize: impl Fn(T) -> R) -> R
+where
+    T: Update + SerializableState + Serialize + DeserializeOwned,
+    R: PartialEq + core::fmt::Debug,
+{
+    let state = SerializableState::serialize(&hasher);
+
+    let json = serde_json::to_string(&hasher).unwrap();
+    let hex: String = state.iter().map(|b| format!("{b:02x}")).collect();
+    assert_eq!(json, format!("\"{hex}\""));
+    let from_json: T = serde_json::from_str(&json).unwrap();
+    assert_eq!(SerializableState::serialize(&from_json), state);
+
+    let mut cbor = Vec::new();
+    ciborium::into_writer(&hasher, &mut cbor).unwrap();
+    let from_cbor: T = ciborium::from_reader(&cbor[..]).unwrap();
+    assert_eq!(SerializableState::serialize(&from_cbor), state);
+
+    let short = format!("\"{}\"", &hex[..hex.len() - 2]);
+    assert!(serde_json::from_str::<T>(&short).is_err());
+
+    let expected = finalize(hasher.chain(TAIL));
+    assert_eq!(finalize(from_json.chain(TAIL)), expected);
+    assert_eq!(finalize(from_cbor.chain(TAIL)), expected);
+    expected
+}
+
+#[test]
+fn xof_serde() {
+    let hasher = TestShake128::default().chain(HEAD);
+    let res = check_round_trip(hasher, |h| {
+        let mut out = [0u8; 200];
+        h.finalize_xof_into(&mut out);
+        out
+    });
+
+    let mut expected = [0u8; 200];
+    sha3::Shake128::default()
+        .chain(HEAD)
+        .chain(TAIL)
+        .finalize_xof_into(&mut expected);
+    assert_eq!(res, expected);
+}
+
+#[test]
+fn ct_variable_serde() {
+    let hasher = TestSha256::<U32>::new_with_prefix(HEAD);
+    let res = check_round_trip(hasher, Digest::finalize);
+    assert_eq!(res, sha2::Sha256::new().chain(HEAD).chain(TAIL).finalize());
+
+    let hasher = TestSha256::<U28>::new_with_prefix(HEAD);
+    let res = check_round_trip(hasher, Digest::finalize);
+    assert_eq!(res, sha2::Sha224::new().chain(HEAD).chain(TAIL).finalize());
+}
+
+#[test]
+fn xof_fixed_serde() {
+    let hasher = XofFixedWrapper::<TestShake128, U32>::new_with_prefix(HEAD);
+    let res = check_round_trip(hasher, Digest::finalize);
+
+    let mut expected = [0u8; 32];
+    sha3::Shake128::default()
+        .chain(HEAD)
+        .chain(TAIL)
+        .finalize_xof_into(&mut expected);
+    assert_eq!(res[..], expected);
+}
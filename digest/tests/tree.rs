@@ -0,0 +1,163 @@
+//! Tests for the tree hashing mode.
+
+#![cfg(feature = "alloc")]
+
+use digest::{
+    Digest,
+    tree::{self, InclusionProof, MerkleTree, TreeConfig},
+};
+use sha2::Sha256;
+
+fn msg(len: usize) -> Vec<u8> {
+    (0..=250u8).cycle().take(len).collect()
+}
+
+#[test]
+fn tree_structure() {
+    let config = TreeConfig::new(4, 2).unwrap();
+    let data = b"aaaabbbbcc";
+
+    let leaf = |l: &[u8]| Sha256::new_with_prefix([0x00]).chain_update(l).finalize();
+    let node = |a: &[u8], b: &[u8]| {
+        Sha256::new_with_prefix([0x01])
+            .chain_update(a)
+            .chain_update(b)
+            .finalize()
+    };
+    let h01 = node(&leaf(b"aaaa"), &leaf(b"bbbb"));
+    // the last leaf is promoted without hashing
+    let expected = node(&h01, &leaf(b"cc"));
+
+    let tree = MerkleTree::<Sha256>::new(config, data);
+    assert_eq!(tree.leaf_hashes().len(), 3);
+    assert_eq!(tree.root(), &expected);
+    assert_eq!(tree::root::<Sha256>(&config, data), expected);
+
+    // empty message results in one empty leaf
+    let tree = MerkleTree::<Sha256>::new(config, &[]);
+    assert_eq!(tree.root(), &leaf(&[]));
+    assert_eq!(tree::root::<Sha256>(&config, &[]), leaf(&[]));
+}
+
+#[test]
+fn tree_root_and_proofs() {
+    let data = msg(1000);
+    for fan_out in [2, 3, 4, 16] {
+        for leaf_size in [1, 7, 64] {
+            let config = TreeConfig::new(leaf_size, fan_out).unwrap();
+            for len in [
+                0,
+                1,
+                leaf_size,
+                leaf_size + 1,
+                5 * leaf_size,
+                17 * leaf_size + 3,
+            ] {
+                let data = &data[..len.min(data.len())];
+                let tree = MerkleTree::<Sha256>::new(config, data);
+                let root = tree.root();
+                assert_eq!(&tree::root::<Sha256>(&config, data), root);
+
+                let leaves: Vec<&[u8]> = match data {
+                    [] => vec![&[]],
+                    _ => data.chunks(leaf_size).collect(),
+                };
+                for (i, leaf) in leaves.iter().enumerate() {
+                    let proof = tree.proof(i).unwrap();
+                    assert_eq!(proof.leaf_index(), i);
+                    assert_eq!(proof.leaf_count(), leaves.len());
+                    assert!(proof.verify(&config, leaf, root).is_ok());
+                    assert!(proof.verify(&config, b"x", root).is_err());
+
+                    let proof = InclusionProof::<Sha256>::new(
+                        (i + 1) % leaves.len(),
+                        leaves.len(),
+                        proof.siblings().to_vec(),
+                    );
+                    if leaves.len() > 1 {
+                        assert!(proof.verify(&config, leaf, root).is_err());
+                    }
+                }
+                assert!(tree.proof(leaves.len()).is_none());
+            }
+        }
+    }
+}
+
+#[test]
+fn tree_invalid_proofs() {
+    let config = TreeConfig::default();
+    let data = msg(5 * 1024);
+    let tree = MerkleTree::<Sha256>::new(config, &data);
+    let root = tree.root();
+    let leaf = &data[2048..3072];
+    let proof = tree.proof(2).unwrap();
+    assert!(proof.verify(&config, leaf, root).is_ok());
+
+    let mut siblings = proof.siblings().to_vec();
+    siblings[0][0] ^= 1;
+    let bad = InclusionProof::<Sha256>::new(2, 5, siblings);
+    assert!(bad.verify(&config, leaf, root).is_err());
+
+    let mut siblings = proof.siblings().to_vec();
+    siblings.push(Default::default());
+    let bad = InclusionProof::<Sha256>::new(2, 5, siblings);
+    assert!(bad.verify(&config, leaf, root).is_err());
+
+    let mut siblings = proof.siblings().to_vec();
+    siblings.pop();
+    let bad = InclusionProof::<Sha256>::new(2, 5, siblings);
+    assert!(bad.verify(&config, leaf, root).is_err());
+
+    let bad = InclusionProof::<Sha256>::new(5, 5, proof.siblings().to_vec());
+    assert!(bad.verify(&config, leaf, root).is_err());
+
+    // leaf longer than leaf size
+    assert!(proof.verify(&config, &data[2048..3073], root).is_err());
+}
+
+/// Leaf position is not committed to by the root hash and must be authenticated separately.
+#[test]
+fn tree_proof_position_is_not_authenticated() {
+    let config = TreeConfig::new(4, 2).unwrap();
+    let data = b"aaaabbbbcccc";
+    let tree = MerkleTree::<Sha256>::new(config, data);
+    let proof = tree.proof(2).unwrap();
+    assert!(proof.verify(&config, b"cccc", tree.root()).is_ok());
+
+    let moved = InclusionProof::<Sha256>::new(1, 2, proof.siblings().to_vec());
+    assert!(moved.verify(&config, b"cccc", tree.root()).is_ok());
+}
+
+#[test]
+fn tree_config() {
+    assert!(TreeConfig::new(0, 2).is_err());
+    assert!(TreeConfig::new(1, 1).is_err());
+
+    let config = TreeConfig::new(32, 4).unwrap();
+    assert_eq!(config.leaf_size(), 32);
+    assert_eq!(config.fan_out(), 4);
+    assert_eq!(config.leaf_prefix(), [0x00]);
+    assert_eq!(config.node_prefix(), [0x01]);
+
+    assert!(config.with_prefixes(b"leaf", b"leaf").is_err());
+    assert!(config.with_prefixes(b"", b"node").is_err());
+    assert!(config.with_prefixes(b"n", b"node").is_err());
+
+    let custom = config.with_prefixes(b"leaf", b"node").unwrap();
+    let data = msg(100);
+    let root = tree::root::<Sha256>(&custom, &data);
+    assert_ne!(root, tree::root::<Sha256>(&config, &data));
+    assert_eq!(&root, MerkleTree::<Sha256>::new(custom, &data).root());
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn tree_parallel() {
+    let config = TreeConfig::new(64, 3).unwrap();
+    for len in [0, 1, 64, 10_000] {
+        let data = msg(len);
+        let tree = MerkleTree::<Sha256>::par_new(config, &data);
+        assert_eq!(tree.root(), MerkleTree::<Sha256>::new(config, &data).root());
+    }
+}
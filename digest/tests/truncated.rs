@@ -0,0 +1,108 @@
+//! Tests for the output truncation wrapper.
+
+#![cfg(feature = "block-api")]
+
+use digest::{
+    CollisionResistance, Digest, FixedOutput, FixedOutputReset, HashMarker, Output, OutputSizeUser,
+    Reset, Truncated, Update,
+    common::hazmat::SerializableState,
+    consts::{U8, U16, U20, U32},
+    truncated::TruncRight,
+    typenum::Unsigned,
+};
+use sha2::Sha256;
+
+/// SHA-256 with declared collision resistance
+#[derive(Clone, Default)]
+struct CrSha256(Sha256);
+
+impl Update for CrSha256 {
+    fn update(&mut self, data: &[u8]) {
+        Digest::update(&mut self.0, data);
+    }
+}
+
+impl OutputSizeUser for CrSha256 {
+    type OutputSize = U32;
+}
+
+impl FixedOutput for CrSha256 {
+    fn finalize_into(self, out: &mut Output<Self>) {
+        FixedOutput::finalize_into(self.0, out);
+    }
+}
+
+impl HashMarker for CrSha256 {}
+
+impl CollisionResistance for CrSha256 {
+    type CollisionResistance = U16;
+}
+
+const MSG: &[u8] = b"truncated hash output";
+
+#[test]
+fn truncated_left() {
+    let full = Sha256::digest(MSG);
+    let res = Truncated::<Sha256, U20>::digest(MSG);
+    assert_eq!(res.len(), 20);
+    assert_eq!(res[..], full[..20]);
+
+    let mut hasher = Truncated::<Sha256, U20>::new();
+    Digest::update(&mut hasher, &MSG[..5]);
+    Digest::update(&mut hasher, &MSG[5..]);
+    assert_eq!(hasher.finalize_fixed_reset()[..], full[..20]);
+    Digest::update(&mut hasher, MSG);
+    assert_eq!(hasher.finalize()[..], full[..20]);
+}
+
+#[test]
+fn truncated_right() {
+    let full = Sha256::digest(MSG);
+    let res = Truncated::<Sha256, U16, TruncRight>::digest(MSG);
+    assert_eq!(res[..], full[16..]);
+}
+
+#[test]
+fn truncated_reset_and_state() {
+    let mut hasher = Truncated::<Sha256, U16>::new();
+    Digest::update(&mut hasher, b"garbage");
+    Reset::reset(&mut hasher);
+    Digest::update(&mut hasher, &MSG[..7]);
+
+    let state = hasher.serialize();
+    let mut restored = Truncated::<Sha256, U16>::deserialize(&state).unwrap();
+    Digest::update(&mut restored, &MSG[7..]);
+    assert_eq!(restored.finalize()[..], Sha256::digest(MSG)[..16]);
+}
+
+#[test]
+fn truncated_collision_resistance() {
+    type Cr<D> = <D as CollisionResistance>::CollisionResistance;
+    assert_eq!(Cr::<CrSha256>::USIZE, 16);
+    assert_eq!(Cr::<Truncated<CrSha256, U32>>::USIZE, 16);
+    assert_eq!(Cr::<Truncated<CrSha256, U20>>::USIZE, 10);
+    assert_eq!(Cr::<Truncated<CrSha256, U8>>::USIZE, 4);
+    assert_eq!(
+        Truncated::<CrSha256, U20>::digest(MSG)[..],
+        Sha256::digest(MSG)[..20]
+    );
+}
+
+#[cfg(feature = "oid")]
+#[test]
+fn truncated_oid() {
+    use digest::const_oid::{AssociatedOid, ObjectIdentifier};
+
+    struct Fingerprint;
+
+    impl AssociatedOid for Fingerprint {
+        const OID: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.6.1.4.1.99999.1");
+    }
+
+    type Sha256Fingerprint = Truncated<Sha256, U16, TruncRight, Fingerprint>;
+    assert_eq!(Sha256Fingerprint::OID, Fingerprint::OID);
+    assert_eq!(
+        Sha256Fingerprint::digest(MSG)[..],
+        Sha256::digest(MSG)[16..]
+    );
+}
@@ -0,0 +1,214 @@
+//! Tests for seekable XOF readers.
+
+#![cfg(feature = "block-api")]
+
+mod block_api {
+    use core::marker::PhantomData;
+    use digest::{
+        HashMarker, Reset,
+        block_api::{
+            Block, BlockSizeUser, Buffer, BufferKindUser, ExtendableOutputCore, UpdateCore,
+            XofReaderCore, XofReaderCoreSeek,
+        },
+        consts::U8,
+    };
+
+    /// Core of primitive counter-mode XOF for testing purposes
+    #[derive(Clone, Debug)]
+    pub struct CounterXofCore<R> {
+        state: u64,
+        _pd: PhantomData<R>,
+    }
+
+    impl<R> Default for CounterXofCore<R> {
+        fn default() -> Self {
+            Self {
+                state: 0,
+                _pd: PhantomData,
+            }
+        }
+    }
+
+    impl<R> BlockSizeUser for CounterXofCore<R> {
+        type BlockSize = U8;
+    }
+
+    impl<R> BufferKindUser for CounterXofCore<R> {
+        type BufferKind = block_buffer::Eager;
+    }
+
+    impl<R> Reset for CounterXofCore<R> {
+        fn reset(&mut self) {
+            self.state = 0;
+        }
+    }
+
+    impl<R> UpdateCore for CounterXofCore<R> {
+        fn update_blocks(&mut self, blocks: &[Block<Self>]) {
+            for block in blocks {
+                self.state = (self.state ^ u64::from_le_bytes(block.0)).rotate_left(7);
+            }
+        }
+    }
+
+    impl<R> HashMarker for CounterXofCore<R> {}
+
+    impl<R: XofReaderCore + From<u64>> ExtendableOutputCore for CounterXofCore<R> {
+        type ReaderCore = R;
+
+        fn finalize_xof_core(&mut self, buffer: &mut Buffer<Self>) -> R {
+            let block = buffer.pad_with_zeros();
+            R::from(self.state ^ u64::from_le_bytes(block.0))
+        }
+    }
+
+    fn gen_block(seed: u64, counter: u64) -> Block<FastReaderCore> {
+        let v = (seed ^ counter.wrapping_mul(0x9E37_79B9_7F4A_7C15)).rotate_left(17);
+        v.to_le_bytes().into()
+    }
+
+    /// Reader core which computes output blocks directly
+    #[derive(Clone, Copy, Debug)]
+    pub struct FastReaderCore {
+        seed: u64,
+        counter: u64,
+    }
+
+    impl From<u64> for FastReaderCore {
+        fn from(seed: u64) -> Self {
+            Self { seed, counter: 0 }
+        }
+    }
+
+    impl BlockSizeUser for FastReaderCore {
+        type BlockSize = U8;
+    }
+
+    impl XofReaderCore for FastReaderCore {
+        fn read_block(&mut self) -> Block<Self> {
+            let block = gen_block(self.seed, self.counter);
+            self.counter += 1;
+            block
+        }
+    }
+
+    impl XofReaderCoreSeek for FastReaderCore {
+        fn block_pos(&self) -> u64 {
+            self.counter
+        }
+
+        fn seek_block(&mut self, pos: u64) -> Result<(), digest::XofSeekError> {
+            self.counter = pos;
+            Ok(())
+        }
+    }
+
+    /// Reader core which relies on the default forward-skipping seek
+    #[derive(Clone, Copy, Debug)]
+    pub struct SlowReaderCore(FastReaderCore);
+
+    impl From<u64> for SlowReaderCore {
+        fn from(seed: u64) -> Self {
+            Self(seed.into())
+        }
+    }
+
+    impl BlockSizeUser for SlowReaderCore {
+        type BlockSize = U8;
+    }
+
+    impl XofReaderCore for SlowReaderCore {
+        fn read_block(&mut self) -> Block<Self> {
+            self.0.read_block()
+        }
+    }
+
+    impl XofReaderCoreSeek for SlowReaderCore {
+        fn block_pos(&self) -> u64 {
+            self.0.block_pos()
+        }
+    }
+}
+
+digest::buffer_xof!(
+    /// Primitive counter-mode XOF for testing purposes
+    pub struct FastXof(block_api::CounterXofCore<block_api::FastReaderCore>);
+    impl: Debug Default Clone BlockSizeUser HashMarker Update Reset ExtendableOutputReset;
+    /// Reader of [`FastXof`]
+    pub struct FastXofReader(block_api::FastReaderCore);
+    impl: XofReaderTraits XofReaderSeek;
+);
+
+digest::buffer_xof!(
+    /// Primitive counter-mode XOF for testing purposes
+    pub struct SlowXof(block_api::CounterXofCore<block_api::SlowReaderCore>);
+    impl: Debug Default Clone BlockSizeUser HashMarker Update Reset ExtendableOutputReset;
+    /// Reader of [`SlowXof`]
+    pub struct SlowXofReader(block_api::SlowReaderCore);
+    impl: XofReaderTraits XofReaderSeek;
+);
+
+use digest::{ExtendableOutput, Update, XofReader, XofReaderSeek, XofSeekError};
+
+const MSG: &[u8] = b"seekable extendable-output function";
+
+fn reference() -> [u8; 100] {
+    let mut buf = [0u8; 100];
+    FastXof::default().chain(MSG).finalize_xof().read(&mut buf);
+    buf
+}
+
+#[test]
+fn same_output() {
+    let mut fast = [0u8; 100];
+    let mut slow = [0u8; 100];
+    FastXof::default().chain(MSG).finalize_xof().read(&mut fast);
+    SlowXof::default().chain(MSG).finalize_xof().read(&mut slow);
+    assert_eq!(fast, slow);
+}
+
+#[test]
+fn fast_seek() {
+    let expected = reference();
+    let mut reader = FastXof::default().chain(MSG).finalize_xof();
+    assert_eq!(reader.current_pos(), 0);
+
+    for pos in [0u64, 1, 7, 8, 9, 63, 64, 65, 90, 3] {
+        reader.seek(pos);
+        assert_eq!(reader.current_pos(), pos);
+        let start = usize::try_from(pos).unwrap();
+        let mut buf = [0u8; 10];
+        reader.read(&mut buf);
+        assert_eq!(buf, expected[start..][..10]);
+        assert_eq!(reader.current_pos(), pos + 10);
+    }
+}
+
+#[test]
+fn slow_seek() {
+    let expected = reference();
+    let mut reader = SlowXof::default().chain(MSG).finalize_xof();
+
+    let mut buf = [0u8; 5];
+    reader.read(&mut buf);
+    assert_eq!(buf, expected[..5]);
+
+    for pos in [10u64, 16, 21, 40, 48, 81] {
+        reader.seek(pos);
+        assert_eq!(reader.current_pos(), pos);
+        let start = usize::try_from(pos).unwrap();
+        reader.read(&mut buf);
+        assert_eq!(buf, expected[start..][..5]);
+    }
+
+    // The default implementation can not seek to already generated blocks
+    assert_eq!(reader.current_pos(), 86);
+    assert_eq!(reader.try_seek(79), Err(XofSeekError));
+    assert_eq!(reader.try_seek(85), Err(XofSeekError));
+    assert_eq!(reader.current_pos(), 86);
+
+    // Skipping forward inside the buffered block
+    reader.seek(87);
+    reader.read(&mut buf);
+    assert_eq!(buf, expected[87..92]);
+}